@@ -1,4 +1,3 @@
-use crate::test_bit;
 use cpu::Cpu;
 use mapped_hardware::MappedHardware;
 use registers::ConditionCode;
@@ -57,6 +56,7 @@ pub enum AddressingMode {
     SR,
     CCR,
     USP,
+    Illegal,
 }
 impl Into<AddressingMode> for usize {
     fn into(self) -> AddressingMode {
@@ -80,7 +80,7 @@ pub fn decode_addressing_mode(bits: usize) -> AddressingMode {
         (0b111, 0b010) => AddressingMode::PCIndirectDisplacementMode,
         (0b111, 0b011) => AddressingMode::PCIndirectIndexed,
         (0b111, 0b100) => AddressingMode::Immediate,
-        (_, _) => unreachable!(),
+        (_, _) => AddressingMode::Illegal,
     }
 }
 
@@ -128,6 +128,23 @@ impl Into<Condition> for usize {
     }
 }
 
+/// Adds the sign-extended 8-bit displacement and the index register of a
+/// brief extension word to `base`. A 68000 ignores bits 8-10, which select
+/// scaling and the full extension format on later processors.
+fn indexed_address(cpu: &mut Cpu, base: u32, extension_word: u16) -> u32 {
+    let reg = (extension_word >> 12 & 0b111) as usize;
+    let index = match extension_word & 0x8000 {
+        0 => cpu.registers.data(reg),
+        _ => cpu.registers.address(reg),
+    };
+    let index = match extension_word & 0x0800 {
+        0 => index as u16 as i16 as u32,
+        _ => index,
+    };
+    let displacement = extension_word as u8 as i8 as u32;
+    base.wrapping_add(displacement).wrapping_add(index)
+}
+
 pub fn read_addressing_mode_address(
     cpu: &mut Cpu,
     bus: &mut impl MappedHardware,
@@ -145,8 +162,9 @@ pub fn read_addressing_mode_address(
     match addressing_mode {
         AddressingMode::Immediate => cpu.read_immediate(bus, size).into(),
         AddressingMode::AbsoluteAddress(DataSize::Word) => {
-            let addr = cpu.read_immediate(bus, &DataSize::Word).into();
-            addr
+            // absolute short addresses are sign-extended
+            let addr: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
+            addr as i16 as i32 as u32
         }
         AddressingMode::AbsoluteAddress(DataSize::LongWord) => {
             let addr = cpu.read_immediate(bus, &DataSize::LongWord).into();
@@ -170,7 +188,7 @@ pub fn read_addressing_mode_address(
                 DataSize::LongWord => 4,
             };
 
-            cpu.registers.set_address(*reg, addr + incr);
+            cpu.registers.set_address(*reg, addr.wrapping_add(incr));
             addr
         }
         AddressingMode::AddressIndirectPreDecrement(ref reg) => {
@@ -192,84 +210,25 @@ pub fn read_addressing_mode_address(
         AddressingMode::AddressIndirectDisplacement(ref reg) => {
             let addr = cpu.registers.address(*reg);
             let displacement: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
-            let addr = (addr as i64 - displacement as u16 as i64) as u32;
-
-            addr
+            addr.wrapping_add(displacement as i16 as u32)
         }
         AddressingMode::AddressIndirectIndexedAndDisplacement(ref reg) => {
-            let reg_addr = cpu.registers.address(*reg);
-
+            let base = cpu.registers.address(*reg);
             let extension_word: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
-            let reg = extension_word >> 12 & 0b111;
-            let index_value = match extension_word & 0x8000 {
-                0b0 => cpu.registers.data(reg as usize),
-                _ => cpu.registers.address(reg as usize),
-                // _ => unreachable!("Not implemented"),
-            };
-
-            let index_size = match test_bit(extension_word.into(), 11) {
-                true => DataSize::Word,
-                false => DataSize::LongWord,
-            };
-
-            let scale = match (extension_word >> 8) & 0b11 {
-                0b00 => 1,
-                0b01 => 2,
-                0b10 => 4,
-                0b11 => 8,
-                _ => unreachable!(),
-            };
-
-            if test_bit(extension_word.into(), 8) {
-                let base_register_supress = test_bit(extension_word.into(), 7);
-                let index_supress = test_bit(extension_word.into(), 6);
-
-                // 00 => reserved
-                // 01 => null displacement
-                // 10 => word
-                // 11 => long
-                let base_displacement_size = (extension_word >> 4) & 0b11;
-
-                match (index_supress, extension_word & 0b111) {
-                    (_, 0b000) => (), // no memory indirect action,
-                    (_, _) => unreachable!("check section 2.2 in 68kpm"),
-                }
-
-                unreachable!()
-            } else {
-                let displacement = (extension_word & 0xff) as u32;
-
-                reg_addr + displacement + index_value * scale
-            }
+            indexed_address(cpu, base, extension_word)
         }
         AddressingMode::PCIndirectDisplacementMode => {
             let pc = cpu.registers.pc();
-            let ext: u32 = cpu.read_immediate(bus, &DataSize::Word).into();
-
-            let displacement = if ext & 0x80 == 0x80 {
-                ext | 0xffff_ff00
-            } else {
-                ext & 0xff
-            };
-
-            let idxreg = ((ext >> 12) & 0x07) as usize;
-            let idxsize = if ext & 0x800 == 0x800 {
-                DataSize::LongWord
-            } else {
-                DataSize::Word
-            };
-            let idx_is_addr = ext & 0x8000 == 0x8000;
-
-            let idx_val = match (idx_is_addr, idxsize) {
-                (true, DataSize::Word) => cpu.registers.address(idxreg) as u16 as u32,
-                (true, DataSize::LongWord) => cpu.registers.address(idxreg),
-                (false, DataSize::Word) => cpu.registers.data(idxreg) as u16 as u32,
-                (false, DataSize::LongWord) => cpu.registers.data(idxreg),
-                _ => unreachable!(),
-            };
-            pc + displacement + idx_val
+            let displacement: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
+            pc.wrapping_add(displacement as i16 as u32)
         }
-        _ => unreachable!("{:?}", addressing_mode),
+        AddressingMode::PCIndirectIndexed => {
+            let pc = cpu.registers.pc();
+            let extension_word: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
+            indexed_address(cpu, pc, extension_word)
+        }
+        // the decoder turns any other mode into an illegal instruction
+        _ => unreachable!("{:?} has no address", addressing_mode),
     }
 }
 
@@ -292,69 +251,41 @@ pub fn read_addressing_mode(
         AddressingMode::DataDirect(ref reg) => map_value(size, cpu.registers.data(*reg)),
         AddressingMode::AddressDirect(ref reg) => map_value(size, cpu.registers.address(*reg)),
         AddressingMode::Immediate => cpu.read_immediate(bus, size).into(),
-        AddressingMode::AbsoluteAddress(DataSize::Word) => {
-            let addr = cpu.read_immediate(bus, &DataSize::Word).into();
-            bus.read_word(addr).into()
-        }
-        AddressingMode::AbsoluteAddress(DataSize::LongWord) => {
-            let addr = cpu.read_immediate(bus, &DataSize::LongWord).into();
-            let v = match size {
-                DataSize::Byte => bus.read_word(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            };
-            v
+        AddressingMode::AbsoluteAddress(_) => {
+            let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
+            cpu.read_memory(bus, size, addr)
         }
         AddressingMode::AddressIndirect(ref reg) => {
             let addr = cpu.registers.address(*reg);
-            match size {
-                DataSize::Byte => bus.read_byte(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            }
+            cpu.read_memory(bus, size, addr)
         }
         AddressingMode::AddressIndirectPostIncrement(ref reg) => {
             let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
-            match size {
-                DataSize::Byte => bus.read_byte(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            }
+            cpu.read_memory(bus, size, addr)
         }
         AddressingMode::AddressIndirectPreDecrement(ref reg) => {
             let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
-            match size {
-                DataSize::Byte => bus.read_byte(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            }
+            cpu.read_memory(bus, size, addr)
         }
         AddressingMode::AddressIndirectDisplacement(ref reg) => {
             let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
-            match size {
-                DataSize::Byte => bus.read_byte(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            }
+            cpu.read_memory(bus, size, addr)
         }
         AddressingMode::AddressIndirectIndexedAndDisplacement(ref reg) => {
             let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
-            match size {
-                DataSize::Byte => bus.read_byte(addr).into(),
-                DataSize::Word => bus.read_word(addr).into(),
-                DataSize::LongWord => bus.read_long(addr).into(),
-            }
+            cpu.read_memory(bus, size, addr)
         }
-        AddressingMode::PCIndirectDisplacementMode => {
+        AddressingMode::PCIndirectDisplacementMode | AddressingMode::PCIndirectIndexed => {
             let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
-            Value::LongWord(addr)
+            cpu.read_program(bus, size, addr)
         }
         AddressingMode::SR => {
             let sr = cpu.registers.sr();
             Value::Word(sr)
         }
         AddressingMode::CCR => Value::Word(cpu.registers.ccr.bits().into()),
-        _ => unreachable!("{:?}", addressing_mode),
+        AddressingMode::USP => Value::LongWord(cpu.registers.usp()),
+        _ => unreachable!("{:?} cannot be read", addressing_mode),
     }
 }
 
@@ -365,6 +296,9 @@ pub fn write_addressing_mode(
     addressing_mode: &AddressingMode,
     value: Value,
 ) {
+    if cpu.is_aborted() {
+        return;
+    }
     match *addressing_mode {
         AddressingMode::DataDirect(reg) => {
             let current: u32 = cpu.registers.data(reg);
@@ -378,17 +312,8 @@ pub fn write_addressing_mode(
         }
         AddressingMode::AddressDirect(reg) => cpu.registers.set_address(reg, value.into()),
         AddressingMode::AddressIndirect(reg) => {
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(cpu.registers.address(reg), value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(cpu.registers.address(reg), value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(cpu.registers.address(reg), value.into());
-                }
-            };
+            let addr = cpu.registers.address(reg);
+            cpu.write_memory(bus, size, addr, value);
         }
         AddressingMode::AddressIndirectPreDecrement(ref reg) => {
             let addr = cpu.registers.address(*reg);
@@ -398,31 +323,11 @@ pub fn write_addressing_mode(
                 DataSize::LongWord => addr.wrapping_sub(4),
             };
             cpu.registers.set_address(*reg, addr);
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(addr, value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(addr, value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(addr, value.into());
-                }
-            };
+            cpu.write_memory(bus, size, addr, value);
         }
         AddressingMode::AddressIndirectPostIncrement(ref reg) => {
             let addr = cpu.registers.address(*reg);
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(addr, value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(addr, value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(addr, value.into());
-                }
-            };
+            cpu.write_memory(bus, size, addr, value);
             let addr = match size {
                 DataSize::Byte => addr.wrapping_add(1),
                 DataSize::Word => addr.wrapping_add(2),
@@ -431,57 +336,24 @@ pub fn write_addressing_mode(
             cpu.registers.set_address(*reg, addr);
         }
         AddressingMode::AddressIndirectDisplacement(ref reg) => {
-            let addr = cpu.registers.address(*reg);
-            let displacement: u16 = cpu.read_immediate(bus, &DataSize::Word).into();
-            let addr = (addr as i64 - displacement as u16 as i64) as u32;
-
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(addr, value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(addr, value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(addr, value.into());
-                }
-            }
+            let addr = read_addressing_mode_address(cpu, bus, size, addressing_mode);
+            cpu.write_memory(bus, size, addr, value);
         }
         AddressingMode::AddressIndirectIndexedAndDisplacement(ref reg) => {
             let addr = read_addressing_mode_address(cpu, bus, &size, &addressing_mode);
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(addr, value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(addr, value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(addr, value.into());
-                }
-            }
+            cpu.write_memory(bus, size, addr, value);
         }
-        AddressingMode::SR => cpu.registers.set_ccr(value.into()),
+        AddressingMode::SR => cpu.set_sr(value.into()),
         AddressingMode::USP => cpu.registers.set_usp(value.into()),
-        AddressingMode::AbsoluteAddress(addr_size) => {
-            let address = cpu.read_immediate(bus, &addr_size).into();
+        AddressingMode::AbsoluteAddress(_) => {
+            let address = read_addressing_mode_address(cpu, bus, size, addressing_mode);
             // let a: u32 = address;
-            match size {
-                DataSize::Byte => {
-                    bus.write_byte(address, value.into());
-                }
-                DataSize::Word => {
-                    bus.write_word(address, value.into());
-                }
-                DataSize::LongWord => {
-                    bus.write_long(address, value.into());
-                }
-            };
+            cpu.write_memory(bus, size, address, value);
         }
         AddressingMode::CCR => {
             // let value: u8 = value.into();
             cpu.registers.ccr = ConditionCode::from_bits_truncate(value.into());
         }
-        _ => unimplemented!("{:?}", addressing_mode),
+        _ => unreachable!("{:?} cannot be written", addressing_mode),
     };
 }
@@ -1,42 +1,193 @@
-use mapped_hardware::MappedHardware;
+use mapped_hardware::{InterruptAcknowledge, MappedHardware};
+
+/// Mappings start and end on a page boundary.
+pub const PAGE_SIZE: u32 = 0x100;
+
+const PAGE_BITS: u32 = 8;
+const PAGES_PER_TABLE: usize = 0x100;
+const TABLE_BITS: u32 = 16;
+
+/// What the bus does with an access no mapped hardware claims.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnmappedAccess {
+    /// Leave the access unanswered so the CPU takes a bus error.
+    #[default]
+    BusError,
+    /// Reads return the given floating bus value and writes are dropped,
+    /// as on systems that do not wire up BERR.
+    OpenBus(u16),
+}
+
+/// Why a device could not be mapped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapError {
+    /// The base or size is not a multiple of `PAGE_SIZE`.
+    Unaligned,
+    /// The range runs past the end of the address space.
+    OutOfRange,
+    /// The range overlaps the device already mapped at `base`.
+    Overlap { base: u32, size: u32 },
+}
+
+struct Mapping {
+    base: u32,
+    size: u32,
+    mirror_mask: u32,
+    hardware: Box<MappedHardware>,
+}
 
 #[derive(Default)]
 pub struct Bus {
-    mapped_hardwares: Vec<Box<MappedHardware>>,
+    mappings: Vec<Mapping>,
+    /// Two-level page table from address to mapping. Each table covers 64
+    /// KiB and holds the mapping index plus one for each of its pages, or
+    /// zero where nothing is mapped.
+    pages: Vec<Option<Box<[u16; PAGES_PER_TABLE]>>>,
+    unmapped_access: UnmappedAccess,
     pub cycles: u64,
 }
 
 impl Bus {
-    pub fn map_hardware(&mut self, hardware: Box<MappedHardware>) {
-        self.mapped_hardwares.push(hardware);
+    /// Maps `hardware` at `size` bytes from `base`. The device sees offsets
+    /// from `base`, masked by `mirror_mask` if it only decodes some of the
+    /// address lines and repeats across the range. A size of zero attaches
+    /// a device that only takes part in ticks, resets and interrupt
+    /// acknowledges.
+    pub fn map_hardware(
+        &mut self,
+        base: u32,
+        size: u32,
+        mirror_mask: Option<u32>,
+        hardware: Box<MappedHardware>,
+    ) -> Result<(), MapError> {
+        if (base | size) & (PAGE_SIZE - 1) != 0 {
+            return Err(MapError::Unaligned);
+        }
+        let end = base as u64 + size as u64;
+        if end > 1 << 32 {
+            return Err(MapError::OutOfRange);
+        }
+        for mapping in &self.mappings {
+            let mapping_end = mapping.base as u64 + mapping.size as u64;
+            if (base as u64) < mapping_end && (mapping.base as u64) < end {
+                return Err(MapError::Overlap {
+                    base: mapping.base,
+                    size: mapping.size,
+                });
+            }
+        }
+
+        self.mappings.push(Mapping {
+            base,
+            size,
+            mirror_mask: mirror_mask.unwrap_or(0xffff_ffff),
+            hardware,
+        });
+        let slot = self.mappings.len() as u16;
+
+        if size > 0 && self.pages.is_empty() {
+            self.pages.resize(1 << (32 - TABLE_BITS), None);
+        }
+        for page in (base as u64 >> PAGE_BITS)..(end >> PAGE_BITS) {
+            let table = self.pages[(page >> (TABLE_BITS - PAGE_BITS)) as usize]
+                .get_or_insert_with(|| Box::new([0; PAGES_PER_TABLE]));
+            table[page as usize % PAGES_PER_TABLE] = slot;
+        }
+        Ok(())
+    }
+
+    pub fn set_unmapped_access(&mut self, unmapped_access: UnmappedAccess) {
+        self.unmapped_access = unmapped_access;
+    }
+
+    /// Looks up the device mapped at `address` and the offset it sees.
+    fn decode(&mut self, address: u32) -> Option<(&mut Box<MappedHardware>, u32)> {
+        let slot = match self.pages.get((address >> TABLE_BITS) as usize) {
+            Some(Some(table)) => table[(address >> PAGE_BITS) as usize % PAGES_PER_TABLE],
+            _ => 0,
+        };
+        if slot == 0 {
+            return None;
+        }
+
+        let mapping = &mut self.mappings[slot as usize - 1];
+        let offset = address.wrapping_sub(mapping.base) & mapping.mirror_mask;
+        Some((&mut mapping.hardware, offset))
     }
 }
 
 impl MappedHardware for Bus {
     fn tick(&mut self, cycles: usize) {
         self.cycles += cycles as u64;
-        for mut hw in self.mapped_hardwares.iter_mut() {
-            hw.tick(cycles);
+        for mapping in &mut self.mappings {
+            mapping.hardware.tick(cycles);
         }
     }
 
-    fn read_word(&mut self, address: u32) -> Option<u16> {
+    fn reset(&mut self) {
+        for mapping in &mut self.mappings {
+            mapping.hardware.reset();
+        }
+    }
+
+    fn set_locked(&mut self, locked: bool) {
+        for mapping in &mut self.mappings {
+            mapping.hardware.set_locked(locked);
+        }
+    }
+
+    fn interrupt_acknowledge(&mut self, level: u8) -> Option<InterruptAcknowledge> {
         self.tick(4);
-        for mut hw in &mut self.mapped_hardwares {
-            if let Some(ref byte) = hw.read_word(address) {
-                return Some(*byte);
+        for mapping in &mut self.mappings {
+            if let Some(acknowledge) = mapping.hardware.interrupt_acknowledge(level) {
+                return Some(acknowledge);
             }
         }
         None
     }
 
+    fn read_byte(&mut self, address: u32) -> Option<u8> {
+        self.tick(4);
+        if let Some((hw, offset)) = self.decode(address) {
+            return hw.read_byte(offset);
+        }
+        match self.unmapped_access {
+            UnmappedAccess::BusError => None,
+            UnmappedAccess::OpenBus(value) if address & 1 == 0 => Some((value >> 8) as u8),
+            UnmappedAccess::OpenBus(value) => Some(value as u8),
+        }
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+        self.tick(4);
+        if let Some((hw, offset)) = self.decode(address) {
+            return hw.write_byte(offset, value);
+        }
+        match self.unmapped_access {
+            UnmappedAccess::BusError => None,
+            UnmappedAccess::OpenBus(_) => Some(value as u16),
+        }
+    }
+
+    fn read_word(&mut self, address: u32) -> Option<u16> {
+        self.tick(4);
+        if let Some((hw, offset)) = self.decode(address) {
+            return hw.read_word(offset);
+        }
+        match self.unmapped_access {
+            UnmappedAccess::BusError => None,
+            UnmappedAccess::OpenBus(value) => Some(value),
+        }
+    }
+
     fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
         self.tick(4);
-        for mut hw in &mut self.mapped_hardwares {
-            if let Some(ref byte) = hw.write_word(address, value) {
-                return Some(*byte);
-            }
+        if let Some((hw, offset)) = self.decode(address) {
+            return hw.write_word(offset, value);
+        }
+        match self.unmapped_access {
+            UnmappedAccess::BusError => None,
+            UnmappedAccess::OpenBus(_) => Some(value),
         }
-        None
     }
 }
@@ -4,7 +4,7 @@ use addressing_mode::{
 };
 use decoder::decode;
 use instruction_set::Instruction;
-use mapped_hardware::MappedHardware;
+use mapped_hardware::{InterruptAcknowledge, MappedHardware};
 use registers::{ConditionCode, Registers, SupervisorStatusRegister};
 
 use std::ops::Add;
@@ -25,7 +25,134 @@ impl Default for InstructionStep {
 
 struct Address(u32);
 
-const ADDRESS_BUS_MASK: u32 = 0x7f_ffff;
+/// Width of the external address bus. Addresses are computed with 32 bits
+/// internally and the lines the package does not have are dropped, so the
+/// top of the address space mirrors the bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AddressBus {
+    /// 68008
+    Bits20,
+    /// 68000 and 68010
+    #[default]
+    Bits24,
+    /// 68020 and later
+    Bits32,
+}
+
+impl AddressBus {
+    pub fn mask(&self) -> u32 {
+        match *self {
+            AddressBus::Bits20 => 0xf_ffff,
+            AddressBus::Bits24 => 0xff_ffff,
+            AddressBus::Bits32 => 0xffff_ffff,
+        }
+    }
+}
+
+/// Status register bits a 68000 implements: T, S, the interrupt mask and
+/// the condition codes.
+const SR_MASK: u16 = 0xa71f;
+
+/// Exception groups in priority order, group 0 being the most urgent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExceptionGroup {
+    Group0,
+    Group1,
+    Group2,
+}
+
+/// Details of a faulting bus cycle, stored in the group 0 stack frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AccessFault {
+    pub address: u32,
+    pub read: bool,
+    pub instruction: bool,
+    pub function_code: u8,
+}
+
+impl AccessFault {
+    /// The first word of the group 0 frame: R/W, I/N and the function code.
+    fn status_word(&self) -> u16 {
+        let mut status = (self.function_code & 0b111) as u16;
+        if !self.instruction {
+            status |= 0b0_1000;
+        }
+        if self.read {
+            status |= 0b1_0000;
+        }
+        status
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exception {
+    BusError(AccessFault),
+    AddressError(AccessFault),
+    IllegalInstruction,
+    ZeroDivide,
+    Chk,
+    TrapV,
+    PrivilegeViolation,
+    Trace,
+    LineA,
+    LineF,
+    UninitializedInterrupt,
+    SpuriousInterrupt,
+    Interrupt(u8), // vector number
+    Trap(u8),
+}
+
+impl Exception {
+    pub fn vector(&self) -> u32 {
+        match *self {
+            Exception::BusError(_) => 2,
+            Exception::AddressError(_) => 3,
+            Exception::IllegalInstruction => 4,
+            Exception::ZeroDivide => 5,
+            Exception::Chk => 6,
+            Exception::TrapV => 7,
+            Exception::PrivilegeViolation => 8,
+            Exception::Trace => 9,
+            Exception::LineA => 10,
+            Exception::LineF => 11,
+            Exception::UninitializedInterrupt => 15,
+            Exception::SpuriousInterrupt => 24,
+            Exception::Interrupt(vector) => vector as u32,
+            Exception::Trap(trap) => 32 + (trap & 0xf) as u32,
+        }
+    }
+
+    pub fn group(&self) -> ExceptionGroup {
+        match *self {
+            Exception::BusError(_) | Exception::AddressError(_) => ExceptionGroup::Group0,
+            Exception::Trap(_) | Exception::TrapV | Exception::Chk | Exception::ZeroDivide => {
+                ExceptionGroup::Group2
+            }
+            _ => ExceptionGroup::Group1,
+        }
+    }
+
+    /// Exceptions that prevent the instruction from executing stack the
+    /// address of the instruction itself rather than the next one.
+    fn stacks_instruction_address(&self) -> bool {
+        matches!(
+            *self,
+            Exception::IllegalInstruction
+                | Exception::LineA
+                | Exception::LineF
+                | Exception::PrivilegeViolation
+        )
+    }
+}
+
+/// Run state of the processor. Only an external reset leaves `Halted`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CpuState {
+    #[default]
+    Running,
+    Stopped,
+    Halted,
+}
 
 // directions
 // in = into cpu
@@ -39,15 +166,34 @@ pub struct Cpu {
 
     immediate: Option<Value>,
 
-    interrupt_requests: Vec<(usize, Option<usize>)>, // (level, Option<address>)
-    stopped: bool,
+    interrupt_requests: Vec<(usize, InterruptAcknowledge)>, // (level, response if no device answers)
+    state: CpuState,
+    address_bus: AddressBus,
+    ipl: u8,           // level presented on the IPL lines
+    nmi_pending: bool, // level 7 is edge triggered
+
+    ir: u16,             // opcode of the current instruction
+    instruction_pc: u32, // address of the current instruction
+    exception: Option<Exception>,
+    processing: Option<Exception>,
     pub debug: bool,
 }
 
 impl Cpu {
     pub fn tick<M: MappedHardware>(&mut self, bus: &M) {}
 
+    /// Runs the processor's reset sequence: enter supervisor mode with
+    /// tracing off and all interrupts masked, then load the supervisor stack
+    /// pointer and program counter from the first two vectors.
     pub fn reset(&mut self, bus: &mut impl MappedHardware) {
+        self.state = CpuState::Running;
+        self.exception = None;
+        self.processing = None;
+        self.interrupt_requests.clear();
+        self.nmi_pending = false;
+        self.registers.set_complete_ccr(0x2700);
+
+        self.immediate = None;
         self.set_pc(0);
         let new_sp = self.read_immediate(bus, &DataSize::LongWord).into();
 
@@ -56,6 +202,26 @@ impl Cpu {
         self.immediate = None;
         let new_pc = self.read_immediate(bus, &DataSize::LongWord).into();
         self.registers.set_pc(new_pc);
+
+        // a fault while fetching the reset vectors halts the processor
+        if self.is_aborted() {
+            self.exception = None;
+            self.state = CpuState::Halted;
+        }
+    }
+
+    pub fn state(&self) -> CpuState {
+        self.state
+    }
+
+    pub fn set_address_bus(&mut self, address_bus: AddressBus) {
+        self.address_bus = address_bus;
+    }
+
+    /// Loads the status register, as MOVE to SR, logic to SR, STOP and RTE
+    /// do. Bits the 68000 does not implement read back as zero.
+    pub fn set_sr(&mut self, value: u16) {
+        self.registers.set_complete_ccr(value & SR_MASK);
     }
 
     pub fn set_pc(&mut self, new_pc: u32) {
@@ -67,53 +233,155 @@ impl Cpu {
     }
 
     pub fn request_auto_interrupt(&mut self, interrupt: usize) {
-        self.interrupt_requests.push((interrupt, None))
+        self.interrupt_requests
+            .push((interrupt, InterruptAcknowledge::Autovector))
     }
 
-    pub fn request_interrupt(&mut self, interrupt: usize, address: usize) {
-        self.interrupt_requests.push((interrupt, Some(address)))
+    pub fn request_interrupt(&mut self, interrupt: usize, vector: u8) {
+        self.interrupt_requests
+            .push((interrupt, InterruptAcknowledge::Vector(vector)))
     }
 
-    fn run_interrupt(&mut self, bus: &mut impl MappedHardware) {
-        if self.interrupt_requests.is_empty() {
-            return;
+    /// Samples the IPL lines. Unlike the latched requests above, the level
+    /// is only serviced for as long as it stays asserted, and the device
+    /// has to answer the acknowledge cycle itself.
+    pub fn set_interrupt_level(&mut self, level: u8) {
+        let level = level & 0b111;
+        if level == 7 && self.ipl != 7 {
+            self.nmi_pending = true;
         }
+        self.ipl = level;
+    }
 
-        let (irqlevel, address) = self.interrupt_requests[0];
-        let address = match address {
-            None => {
-                let intermediate = (irqlevel * 4) + 60;
-                bus.read_long(intermediate as u32).unwrap() as usize
+    /// Flags an exception to be processed at the end of the current
+    /// instruction. A pending exception of a higher priority group wins.
+    pub fn raise_exception(&mut self, exception: Exception) {
+        match self.exception {
+            Some(ref pending) if pending.group() <= exception.group() => (),
+            _ => self.exception = Some(exception),
+        }
+    }
+
+    fn process_pending_exception(&mut self, bus: &mut impl MappedHardware) -> Option<Exception> {
+        let first = self.exception?;
+        while let Some(exception) = self.exception.take() {
+            self.processing = Some(exception);
+            self.process_exception(bus, exception);
+            let faulted = match self.exception {
+                Some(ref fault) => fault.group() == ExceptionGroup::Group0,
+                None => false,
+            };
+            self.processing = None;
+            // a bus or address error while processing another one is a
+            // double bus fault, which halts the processor
+            if faulted && exception.group() == ExceptionGroup::Group0 {
+                self.exception = None;
+                self.state = CpuState::Halted;
+                break;
             }
-            Some(address) => address + 0,
-        };
+        }
+        Some(first)
+    }
 
-        let pc = self.registers.pc();
+    fn process_exception(&mut self, bus: &mut impl MappedHardware, exception: Exception) {
+        let sr = self.registers.sr();
+        let mut status = self.registers.system_status_register;
+        status.insert(SupervisorStatusRegister::S);
+        status.remove(SupervisorStatusRegister::T);
+        self.registers
+            .set_complete_ccr((status.bits() as u16) << 8 | (sr & 0xff));
+
+        let pc = if exception.stacks_instruction_address() {
+            self.instruction_pc
+        } else {
+            self.registers.pc()
+        };
         self.push_stack(bus, DataSize::LongWord, Value::LongWord(pc));
-        let ccr = self.registers.complete_ccr();
-        self.push_stack(bus, DataSize::Word, Value::Word(ccr));
+        self.push_stack(bus, DataSize::Word, Value::Word(sr));
+
+        match exception {
+            Exception::BusError(fault) | Exception::AddressError(fault) => {
+                let ir = self.ir;
+                self.push_stack(bus, DataSize::Word, Value::Word(ir));
+                self.push_stack(bus, DataSize::LongWord, Value::LongWord(fault.address));
+                self.push_stack(bus, DataSize::Word, Value::Word(fault.status_word()));
+            }
+            _ => (),
+        }
+
+        let handler = self
+            .read_memory(bus, &DataSize::LongWord, exception.vector() * 4)
+            .into();
+        self.set_pc(handler);
+    }
 
-        if irqlevel + 0 <= 7 {
-            let bits = self.registers.system_status_register.bits() & 0xf8;
-            self.registers.system_status_register =
-                SupervisorStatusRegister::from_bits_truncate(bits | (irqlevel + 0) as u8);
+    /// Takes the oldest of the highest pending interrupts above the IPL
+    /// mask. Level 7 is not maskable.
+    fn run_interrupt(&mut self, bus: &mut impl MappedHardware) -> Option<Exception> {
+        let mask = (self.registers.system_status_register.bits() & 0b111) as usize;
+        let mut request: Option<usize> = None;
+        for (i, &(level, _)) in self.interrupt_requests.iter().enumerate() {
+            if level <= mask && level != 7 {
+                continue;
+            }
+            match request {
+                Some(r) if self.interrupt_requests[r].0 >= level => (),
+                _ => request = Some(i),
+            }
         }
 
-        self.interrupt_requests.remove(0);
-        self.set_pc(address as u32);
-        self.stopped = false;
+        let requested_level = request.map_or(0, |r| self.interrupt_requests[r].0);
+        let ipl = self.ipl as usize;
+        let line_active = (ipl > mask && ipl != 7) || (ipl == 7 && self.nmi_pending);
+        let (level, fallback) = if line_active && ipl > requested_level {
+            self.nmi_pending = false;
+            (ipl, InterruptAcknowledge::Spurious)
+        } else {
+            self.interrupt_requests.remove(request?)
+        };
+        let level = level as u8 & 0b111;
+        let exception = match bus.interrupt_acknowledge(level).unwrap_or(fallback) {
+            InterruptAcknowledge::Vector(0x0f) => Exception::UninitializedInterrupt,
+            InterruptAcknowledge::Vector(vector) => Exception::Interrupt(vector),
+            InterruptAcknowledge::Autovector => Exception::Interrupt(24 + level),
+            InterruptAcknowledge::Spurious => Exception::SpuriousInterrupt,
+        };
+
+        self.state = CpuState::Running;
+        self.raise_exception(exception);
+        let taken = self.process_pending_exception(bus);
+        let bits = self.registers.system_status_register.bits() & 0xf8;
+        self.registers.system_status_register =
+            SupervisorStatusRegister::from_bits_truncate(bits | level);
+        taken
     }
 
-    pub fn execute_next_instruction(&mut self, bus: &mut impl MappedHardware) {
-        self.run_interrupt(bus);
+    /// Executes one instruction and returns the exception it caused, if any,
+    /// after the processor has vectored to its handler.
+    pub fn execute_next_instruction(&mut self, bus: &mut impl MappedHardware) -> Option<Exception> {
+        if self.state == CpuState::Halted {
+            return None;
+        }
+        if let Some(interrupt) = self.run_interrupt(bus) {
+            return Some(interrupt);
+        }
+        // a stopped processor runs no bus cycles, but time still passes for
+        // the devices that will wake it
+        if self.state == CpuState::Stopped {
+            bus.tick(4);
+            return None;
+        }
         self.immediate = None;
         let pc = self.registers.pc();
-        let op = bus.read_word(self.registers.pc()).unwrap();
+        let op: u16 = self.read_program(bus, &DataSize::Word, pc).into();
 
-        if self.stopped {
-            return;
+        if self.is_aborted() {
+            return self.process_pending_exception(bus);
         }
 
+        self.ir = op;
+        self.instruction_pc = pc;
+
         self.registers.pc_increment();
         self.registers.pc_increment();
         let instr = decode(op as usize);
@@ -121,7 +389,26 @@ impl Cpu {
         if self.debug {
             println!("{:04X} {:04X} {:?} {:?}", pc, op, self.registers, instr2);
         }
+        // the trace bit is sampled before the instruction runs, so an
+        // instruction that sets T is not itself traced
+        let trace = self
+            .registers
+            .system_status_register
+            .contains(SupervisorStatusRegister::T);
         self.execute_instruction(bus, instr);
+
+        let aborted = self.is_aborted();
+        let exception = self.process_pending_exception(bus);
+        if trace && !aborted {
+            // a traced STOP takes the trace exception instead of stopping
+            if self.state == CpuState::Stopped {
+                self.state = CpuState::Running;
+            }
+            self.raise_exception(Exception::Trace);
+            let trace = self.process_pending_exception(bus);
+            return exception.or(trace);
+        }
+        exception
     }
 
     pub fn read_immediate(&mut self, bus: &mut impl MappedHardware, size: &DataSize) -> Value {
@@ -130,10 +417,11 @@ impl Cpu {
             _ => (),
         }
 
+        // byte immediates occupy the low half of an extension word
+        let pc = self.registers.pc();
         let immediate = match size {
-            DataSize::Byte => Value::Byte(bus.read_byte(self.registers.pc()).unwrap()),
-            DataSize::Word => Value::Word(bus.read_word(self.registers.pc()).unwrap()),
-            DataSize::LongWord => Value::LongWord(bus.read_long(self.registers.pc()).unwrap()),
+            DataSize::Byte => Value::Byte(self.read_program(bus, &DataSize::Word, pc).into()),
+            _ => self.read_program(bus, size, pc),
         };
         match size {
             DataSize::Byte => self.registers.displace_pc(Value::Byte(2)),
@@ -145,20 +433,156 @@ impl Cpu {
         immediate
     }
 
-    fn push_stack(&mut self, bus: &mut impl MappedHardware, size: DataSize, value: Value) {
-        match size {
-            DataSize::Word => {
-                let sp = self.registers.sp();
-                self.registers.set_sp(sp - 2);
-                bus.write_word(self.registers.sp(), value.into());
+    /// Reads data on behalf of the current instruction.
+    pub fn read_memory(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: &DataSize,
+        address: u32,
+    ) -> Value {
+        self.read_bus(bus, size, address, false)
+    }
+
+    /// Reads from program space, as instruction fetches and PC relative
+    /// operands do.
+    pub fn read_program(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: &DataSize,
+        address: u32,
+    ) -> Value {
+        self.read_bus(bus, size, address, true)
+    }
+
+    fn read_bus(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: &DataSize,
+        address: u32,
+        program: bool,
+    ) -> Value {
+        if self.is_aborted() {
+            return Value::from_raw(*size, 0);
+        }
+        if *size != DataSize::Byte && address & 1 == 1 {
+            let fault = self.access_fault(address, true, program);
+            self.raise_exception(Exception::AddressError(fault));
+            return Value::from_raw(*size, 0);
+        }
+
+        // long words are two word cycles, each put on the bus separately
+        let mask = self.address_bus.mask();
+        let value = match size {
+            DataSize::Byte => bus.read_byte(address & mask).map(Value::Byte),
+            DataSize::Word => bus.read_word(address & mask).map(Value::Word),
+            DataSize::LongWord => {
+                let high = bus.read_word(address & mask);
+                let low = bus.read_word(address.wrapping_add(2) & mask);
+                match (high, low) {
+                    (Some(high), Some(low)) => {
+                        Some(Value::LongWord((high as u32) << 16 | low as u32))
+                    }
+                    _ => None,
+                }
             }
+        };
+        match value {
+            Some(value) => value,
+            None => {
+                let fault = self.access_fault(address, true, program);
+                self.raise_exception(Exception::BusError(fault));
+                Value::from_raw(*size, 0)
+            }
+        }
+    }
+
+    /// Writes data on behalf of the current instruction.
+    pub fn write_memory(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: &DataSize,
+        address: u32,
+        value: Value,
+    ) {
+        if self.is_aborted() {
+            return;
+        }
+        if *size != DataSize::Byte && address & 1 == 1 {
+            let fault = self.access_fault(address, false, false);
+            self.raise_exception(Exception::AddressError(fault));
+            return;
+        }
+
+        let mask = self.address_bus.mask();
+        let written = match size {
+            DataSize::Byte => bus.write_byte(address & mask, value.into()).is_some(),
+            DataSize::Word => bus.write_word(address & mask, value.into()).is_some(),
             DataSize::LongWord => {
-                let sp = self.registers.sp();
-                self.registers.set_sp(sp - 4);
-                bus.write_long(self.registers.sp(), value.into());
+                let value: u32 = value.into();
+                bus.write_word(address & mask, (value >> 16) as u16)
+                    .is_some()
+                    && bus
+                        .write_word(address.wrapping_add(2) & mask, value as u16)
+                        .is_some()
+            }
+        };
+        if !written {
+            let fault = self.access_fault(address, false, false);
+            self.raise_exception(Exception::BusError(fault));
+        }
+    }
+
+    /// Once a bus or address error is pending, or the instruction turned out
+    /// not to be executable, the rest of it is abandoned and no further bus
+    /// cycles are run.
+    pub fn is_aborted(&self) -> bool {
+        match self.exception {
+            Some(ref exception) => {
+                exception.group() == ExceptionGroup::Group0
+                    || exception.stacks_instruction_address()
             }
+            None => false,
+        }
+    }
+
+    fn access_fault(&self, address: u32, read: bool, program: bool) -> AccessFault {
+        let supervisor = self
+            .registers
+            .system_status_register
+            .contains(SupervisorStatusRegister::S);
+        let function_code = match (supervisor, program) {
+            (false, false) => 1,
+            (false, true) => 2,
+            (true, false) => 5,
+            (true, true) => 6,
+        };
+        AccessFault {
+            address,
+            read,
+            instruction: self.processing.is_none(),
+            function_code,
+        }
+    }
+
+    fn push_stack(&mut self, bus: &mut impl MappedHardware, size: DataSize, value: Value) {
+        let sp = match size {
+            DataSize::Word => self.registers.sp().wrapping_sub(2),
+            DataSize::LongWord => self.registers.sp().wrapping_sub(4),
+            _ => unreachable!(),
+        };
+        self.registers.set_sp(sp);
+        self.write_memory(bus, &size, sp, value);
+    }
+
+    fn pop_stack(&mut self, bus: &mut impl MappedHardware, size: DataSize) -> Value {
+        let sp = self.registers.sp();
+        let value = self.read_memory(bus, &size, sp);
+        match size {
+            DataSize::Word => self.registers.set_sp(sp.wrapping_add(2)),
+            DataSize::LongWord => self.registers.set_sp(sp.wrapping_add(4)),
             _ => unreachable!(),
         }
+        value
     }
 
     fn read_addressing_mode(
@@ -180,31 +604,103 @@ impl Cpu {
         write_addressing_mode(self, bus, size, addressing_mode, value)
     }
 
+    /// Reads an operand, passes it through `operation` and writes the result
+    /// back. A memory operand's effective address is calculated once, so
+    /// increments, decrements and extension words are not repeated by the
+    /// write. Nothing happens past a faulting read.
+    fn read_modify_write(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: &DataSize,
+        addressing_mode: &AddressingMode,
+        operation: impl FnOnce(&mut Self, Value) -> Value,
+    ) {
+        match addressing_mode {
+            AddressingMode::DataDirect(_) | AddressingMode::AddressDirect(_) => {
+                let value = self.read_addressing_mode(bus, size, addressing_mode);
+                if self.is_aborted() {
+                    return;
+                }
+                let result = operation(self, value);
+                self.write_addressing_mode(bus, size, addressing_mode, result);
+            }
+            _ => {
+                self.immediate = None;
+                let address = read_addressing_mode_address(self, bus, size, addressing_mode);
+                let value = self.read_memory(bus, size, address);
+                if self.is_aborted() {
+                    return;
+                }
+                let result = operation(self, value);
+                self.write_memory(bus, size, address, result);
+            }
+        }
+    }
+
     fn execute_instruction(&mut self, bus: &mut impl MappedHardware, instruction: Instruction) {
+        if is_privileged(&instruction) && !self.registers.is_supervisor() {
+            self.raise_exception(Exception::PrivilegeViolation);
+            return;
+        }
+
         match instruction {
+            Instruction::ADDQ(size, value, AddressingMode::AddressDirect(reg)) => {
+                self.adda(bus, size, value, reg)
+            }
             Instruction::ADDQ(size, value, dest) => self.add(bus, size, value, dest),
+            Instruction::ADDA(size, value, AddressingMode::AddressDirect(reg)) => {
+                self.adda(bus, size, value, reg)
+            }
             Instruction::ADD(size, value, dest) => self.add(bus, size, value, dest),
             Instruction::SUBI(size, value, dest) => self.sub(bus, size, value, dest),
-            Instruction::SUBA(size, value, dest) => self.sub(bus, size, value, dest),
+            Instruction::SUBA(size, value, AddressingMode::AddressDirect(reg)) => {
+                self.suba(bus, size, value, reg)
+            }
+            Instruction::SUBQ(size, value, AddressingMode::AddressDirect(reg)) => {
+                self.suba(bus, size, value, reg)
+            }
             Instruction::SUBQ(size, value, dest) => self.sub(bus, size, value, dest),
             Instruction::SUB(size, value, dest) => self.sub(bus, size, value, dest),
             Instruction::ADDI(size, value, dest) => self.add(bus, size, value, dest),
             Instruction::AND(size, source, dest) => self.and(bus, size, source, dest),
+            Instruction::ANDI(_, _, AddressingMode::SR) => {
+                self.logic_to_status(bus, AddressingMode::SR, |sr, value| sr & value)
+            }
+            Instruction::ANDI(_, _, AddressingMode::CCR) => {
+                self.logic_to_status(bus, AddressingMode::CCR, |ccr, value| ccr & value)
+            }
+            Instruction::ORI(_, _, AddressingMode::SR) => {
+                self.logic_to_status(bus, AddressingMode::SR, |sr, value| sr | value)
+            }
+            Instruction::ORI(_, _, AddressingMode::CCR) => {
+                self.logic_to_status(bus, AddressingMode::CCR, |ccr, value| ccr | value)
+            }
+            Instruction::EORI(_, _, AddressingMode::SR) => {
+                self.logic_to_status(bus, AddressingMode::SR, |sr, value| sr ^ value)
+            }
+            Instruction::EORI(_, _, AddressingMode::CCR) => {
+                self.logic_to_status(bus, AddressingMode::CCR, |ccr, value| ccr ^ value)
+            }
             Instruction::ANDI(size, source, dest) => self.and(bus, size, source, dest),
             Instruction::CMP(size, source, dest) => self.cmp(bus, size, source, dest),
             Instruction::CMPI(size, ea) => self.cmp(bus, size, AddressingMode::Immediate, ea),
+            Instruction::CMPA(size, source, dest) => self.cmpa(bus, size, source, dest),
+            Instruction::CMPM(size, source, dest) => self.cmp(bus, size, source, dest),
             Instruction::DB(condition, source, ea) => self.db(bus, condition, source, ea),
             Instruction::ORI(size, source, dest) => self.or(bus, size, source, dest),
             Instruction::OR(size, source, dest) => self.or(bus, size, source, dest),
             Instruction::EORI(size, source, dest) => self.eor(bus, size, source, dest),
             Instruction::EOR(size, source, dest) => self.eor(bus, size, source, dest),
             Instruction::NOP => self.nop(),
+            Instruction::RESET => self.reset_instruction(bus),
             Instruction::BCC(size, condition, ea) => self.bcc(bus, size, condition, ea),
             Instruction::BRA(label) => self.bra(bus, label),
             Instruction::LEA(ea, reg) => self.lea(bus, ea, reg),
             Instruction::TST(size, ea) => self.tst(bus, size, ea),
             Instruction::BTST(size, bit, ea) => self.btst(bus, size, bit, ea),
-            // Instruction::BSET(size, bit, ea) => self.bset(bus, size, bit, ea),
+            Instruction::BCHG(size, bit, ea) => self.bchg(bus, size, bit, ea),
+            Instruction::BCLR(size, bit, ea) => self.bclr(bus, size, bit, ea),
+            Instruction::BSET(size, bit, ea) => self.bset(bus, size, bit, ea),
             Instruction::MOVE(size, source, dest) => self.move_(bus, size, source, dest),
             Instruction::MOVEQ(size, source, dest) => self.move_(bus, size, source, dest),
             Instruction::MOVEM(size, source, direction) => self.movem(bus, size, source, direction),
@@ -213,46 +709,108 @@ impl Cpu {
             Instruction::JSR(ea) => self.jsr(bus, ea),
             Instruction::RTS => self.rts(bus),
             Instruction::RTE => self.rte(bus),
+            Instruction::RTR => self.rtr(bus),
+            Instruction::TRAP(AddressingMode::Vector(trap)) => {
+                self.raise_exception(Exception::Trap(trap as u8))
+            }
+            Instruction::TRAPV => {
+                if self.registers.ccr.contains(ConditionCode::V) {
+                    self.raise_exception(Exception::TrapV);
+                }
+            }
+            Instruction::CHK(_, bound, AddressingMode::DataDirect(reg)) => {
+                self.chk(bus, bound, reg)
+            }
             Instruction::BSR(label) => self.bsr(bus, label),
-            Instruction::LSLD(size, count, dest) => self.lsl(bus, size, count, dest),
-            Instruction::LSRD(size, count, dest) => self.lsr(bus, size, count, dest),
-            Instruction::ASLD(size, count, dest) => self.asl(bus, size, count, dest),
-            Instruction::ASRD(size, count, dest) => self.asr(bus, size, count, dest),
-            Instruction::RORD(size, count, dest) => self.ror(bus, size, count, dest),
-            Instruction::ROLD(size, count, dest) => self.rol(bus, size, count, dest),
+            Instruction::LSLD(size, count, dest) => {
+                self.shift(bus, Shift::LogicalLeft, size, count, dest)
+            }
+            Instruction::LSRD(size, count, dest) => {
+                self.shift(bus, Shift::LogicalRight, size, count, dest)
+            }
+            Instruction::ASLD(size, count, dest) => {
+                self.shift(bus, Shift::ArithmeticLeft, size, count, dest)
+            }
+            Instruction::ASRD(size, count, dest) => {
+                self.shift(bus, Shift::ArithmeticRight, size, count, dest)
+            }
+            Instruction::ROLD(size, count, dest) => {
+                self.shift(bus, Shift::RotateLeft, size, count, dest)
+            }
+            Instruction::RORD(size, count, dest) => {
+                self.shift(bus, Shift::RotateRight, size, count, dest)
+            }
+            Instruction::ROXLD(size, count, dest) => {
+                self.shift(bus, Shift::RotateExtendLeft, size, count, dest)
+            }
+            Instruction::ROXRD(size, count, dest) => {
+                self.shift(bus, Shift::RotateExtendRight, size, count, dest)
+            }
             Instruction::LINK(reg, displacement) => {
                 self.link(bus, DataSize::Word, reg, displacement)
             }
+            Instruction::UNLK(AddressingMode::AddressDirect(reg)) => self.unlk(bus, reg),
             Instruction::PEA(ea) => self.pea(bus, ea),
             Instruction::STOP(ccr) => self.stop(bus, ccr),
-            Instruction::SWAP(DataSize::Word, AddressingMode::DataDirect(reg)) => self.swap(reg),
+            Instruction::SWAP(_, AddressingMode::DataDirect(reg)) => self.swap(reg),
+            Instruction::EXG(_, first, second) => self.exg(bus, first, second),
+            Instruction::EXT(size, AddressingMode::DataDirect(reg)) => self.ext(size, reg),
+            Instruction::TAS(_, ea) => self.tas(bus, ea),
+            Instruction::ST(_, condition, ea) => self.scc(bus, condition, ea),
             Instruction::NOT(size, ea) => self.not(bus, size, ea),
-            _ => unimplemented!("{:?}", instruction),
+            Instruction::MULU(_, source, AddressingMode::DataDirect(reg)) => {
+                self.mulu(bus, source, reg)
+            }
+            Instruction::MULS(_, source, AddressingMode::DataDirect(reg)) => {
+                self.muls(bus, source, reg)
+            }
+            Instruction::DIVU(_, source, AddressingMode::DataDirect(reg)) => {
+                self.divu(bus, source, reg)
+            }
+            Instruction::DIVS(_, source, AddressingMode::DataDirect(reg)) => {
+                self.divs(bus, source, reg)
+            }
+            Instruction::ADDX(size, source, dest) => self.addx(bus, size, source, dest),
+            Instruction::SUBX(size, source, dest) => self.subx(bus, size, source, dest),
+            Instruction::NEG(size, ea) => self.neg(bus, size, ea),
+            Instruction::NEGX(size, ea) => self.negx(bus, size, ea),
+            Instruction::MOVEP(size, source, dest) => self.movep(bus, size, source, dest),
+            Instruction::ABCD(source, dest) => self.abcd(bus, source, dest),
+            Instruction::SBCD(source, dest) => self.sbcd(bus, source, dest),
+            Instruction::NBCD(ea) => self.nbcd(bus, ea),
+            Instruction::LineA(_) => self.raise_exception(Exception::LineA),
+            Instruction::LineF(_) => self.raise_exception(Exception::LineF),
+            Instruction::Illegal => self.raise_exception(Exception::IllegalInstruction),
+            // every opcode is handled above; what is left are operand shapes
+            // the decoder never produces, such as MULU to an address register
+            _ => self.raise_exception(Exception::IllegalInstruction),
         }
     }
 
     fn read_condition_code(&mut self, condition_code: Condition) -> bool {
+        let ccr = self.registers.ccr;
+        let c = ccr.contains(ConditionCode::C);
+        let v = ccr.contains(ConditionCode::V);
+        let z = ccr.contains(ConditionCode::Z);
+        let n = ccr.contains(ConditionCode::N);
+
         match condition_code {
-            Condition::CC => !self.registers.ccr.contains(ConditionCode::C), // Carry Clear
-            LS => {
-                self.registers.ccr.contains(ConditionCode::N)
-                    || self.registers.ccr.contains(ConditionCode::Z)
-            } // Lower or Same
-            // CS, // Carry Set
-            // LT, // Less Than
-            Condition::EQ => self.registers.ccr.contains(ConditionCode::Z), // EQual
-            // MI, // MInus
-            Condition::F => false, // False (Never true)
-            Condition::NE => !self.registers.ccr.contains(ConditionCode::Z), // Not Equal
-            // Condition::GE, // Greater than or Equal
-            // PL, // Plus
-            // GT, // Greater Than
-            Condition::T => true, // True (always true)
-            // HI, // HIgher
-            // VC, // oVerflow Clear
-            // LE, // Less than or Equal
-            // VS, // oVerflow Set
-            _ => unimplemented!("{:?}", condition_code),
+            Condition::T => true,          // True (always true)
+            Condition::F => false,         // False (Never true)
+            Condition::HI => !c && !z,     // HIgher
+            Condition::LS => c || z,       // Lower or Same
+            Condition::CC => !c,           // Carry Clear
+            Condition::CS => c,            // Carry Set
+            Condition::NE => !z,           // Not Equal
+            Condition::EQ => z,            // EQual
+            Condition::VC => !v,           // oVerflow Clear
+            Condition::VS => v,            // oVerflow Set
+            Condition::PL => !n,           // PLus
+            Condition::MI => n,            // MInus
+            Condition::GE => n == v,       // Greater than or Equal
+            Condition::LT => n != v,       // Less Than
+            Condition::GT => !z && n == v, // Greater Than
+            Condition::LE => z || n != v,  // Less than or Equal
         }
     }
 
@@ -260,16 +818,17 @@ impl Cpu {
 
     fn nop(&self) {}
 
+    /// Asserts the reset line for 124 clocks so the peripherals reinitialise.
+    /// The processor itself is left untouched.
+    fn reset_instruction(&mut self, bus: &mut impl MappedHardware) {
+        bus.reset();
+        bus.tick(124);
+    }
+
     fn stop(&mut self, bus: &mut impl MappedHardware, ccr: AddressingMode) {
         let ccr: u16 = self.read_addressing_mode(bus, &DataSize::Word, &ccr).into();
-        if self
-            .registers
-            .system_status_register
-            .contains(SupervisorStatusRegister::S)
-        {
-            self.registers.set_complete_ccr(ccr);
-        }
-        self.stopped = true;
+        self.set_sr(ccr);
+        self.state = CpuState::Stopped;
     }
 
     fn bra(&mut self, bus: &mut impl MappedHardware, addressing_mode: AddressingMode) {
@@ -350,28 +909,54 @@ impl Cpu {
 
         let label = self.read_addressing_mode(bus, &DataSize::Word, &addressing_mode);
 
-        self.push_stack(bus, DataSize::LongWord, Value::LongWord(before_pc - 2));
+        self.push_stack(bus, DataSize::LongWord, Value::LongWord(before_pc.wrapping_sub(2)));
         self.registers.set_pc(before_pc);
         self.registers.displace_pc(label);
     }
 
     fn rts(&mut self, bus: &mut impl MappedHardware) {
-        let sp = self.registers.sp();
-        let new_addr = bus.read_long(sp);
-        self.registers.set_pc(new_addr.unwrap());
-        self.registers.set_sp(sp + 4);
+        let new_pc = self.pop_stack(bus, DataSize::LongWord).into();
+        self.registers.set_pc(new_pc);
     }
 
     fn rte(&mut self, bus: &mut impl MappedHardware) {
-        let sp = self.registers.sp();
-        let new_addr = bus.read_word(sp);
-        self.registers.set_complete_ccr(new_addr.unwrap());
-        self.registers.set_sp(sp + 2);
+        let sr = self.pop_stack(bus, DataSize::Word).into();
+        let new_pc = self.pop_stack(bus, DataSize::LongWord).into();
+        if self.is_aborted() {
+            return;
+        }
+        self.set_sr(sr);
+        self.registers.set_pc(new_pc);
+    }
 
-        let sp = self.registers.sp();
-        let new_addr = bus.read_long(sp);
-        self.registers.set_pc(new_addr.unwrap());
-        self.registers.set_sp(sp + 4);
+    fn rtr(&mut self, bus: &mut impl MappedHardware) {
+        let ccr: u16 = self.pop_stack(bus, DataSize::Word).into();
+        let new_pc = self.pop_stack(bus, DataSize::LongWord).into();
+        if self.is_aborted() {
+            return;
+        }
+        self.registers.set_ccr(ccr as u8);
+        self.registers.set_pc(new_pc);
+    }
+
+    /// Traps through the CHK vector when the low word of the register is
+    /// negative or greater than the upper bound, with N telling which.
+    fn chk(&mut self, bus: &mut impl MappedHardware, bound: AddressingMode, register: usize) {
+        let bound: u16 = self
+            .read_addressing_mode(bus, &DataSize::Word, &bound)
+            .into();
+        if self.is_aborted() {
+            return;
+        }
+        let value = self.registers.data(register) as i16;
+
+        if value < 0 {
+            self.registers.ccr.insert(ConditionCode::N);
+            self.raise_exception(Exception::Chk);
+        } else if value > bound as i16 {
+            self.registers.ccr.remove(ConditionCode::N);
+            self.raise_exception(Exception::Chk);
+        }
     }
 
     fn add(
@@ -387,66 +972,271 @@ impl Cpu {
         let (result, flags) = destination_value.add_cc(size, value);
 
         self.write_addressing_mode(bus, &size, &destination, result);
+        if self.is_aborted() {
+            return;
+        }
         self.registers.ccr = flags;
     }
 
-    fn sub(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        value: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let value = self.read_addressing_mode(bus, &size, &value);
-        let destination_value = self.read_addressing_mode(bus, &size, &destination);
+    fn mulu(&mut self, bus: &mut impl MappedHardware, source: AddressingMode, register: usize) {
+        let multiplier: u16 = self
+            .read_addressing_mode(bus, &DataSize::Word, &source)
+            .into();
+        if self.is_aborted() {
+            return;
+        }
+        let multiplicand = self.registers.data(register) as u16;
+        let result = multiplicand as u32 * multiplier as u32;
 
-        let (result, flags) = destination_value.sub_cc(size, value);
+        self.registers.set_data(register, result);
+        self.set_multiply_flags(result);
+        bus.tick(34 + 2 * multiplier.count_ones() as usize);
+    }
 
-        self.write_addressing_mode(bus, &size, &destination, result);
-        self.registers.ccr = flags;
+    fn muls(&mut self, bus: &mut impl MappedHardware, source: AddressingMode, register: usize) {
+        let multiplier: u16 = self
+            .read_addressing_mode(bus, &DataSize::Word, &source)
+            .into();
+        if self.is_aborted() {
+            return;
+        }
+        let multiplicand = self.registers.data(register) as u16;
+        let result = (multiplicand as i16 as i32 * multiplier as i16 as i32) as u32;
+
+        self.registers.set_data(register, result);
+        self.set_multiply_flags(result);
+        // one extra step for every 01 or 10 pair in the multiplier, with a
+        // zero shifted in below bit 0
+        let pairs = multiplier as u32 ^ (multiplier as u32) << 1;
+        bus.tick(34 + 2 * (pairs & 0xffff).count_ones() as usize);
     }
 
-    fn or(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        value: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let destination = match destination {
-            AddressingMode::Immediate => AddressingMode::CCR,
-            _ => destination,
-        };
-        let value = self.read_addressing_mode(bus, &size, &value);
-        let destination_value = self.read_addressing_mode(bus, &size, &destination);
+    fn set_multiply_flags(&mut self, result: u32) {
+        self.registers
+            .ccr
+            .set(ConditionCode::N, result & 0x8000_0000 != 0);
+        self.registers.ccr.set(ConditionCode::Z, result == 0);
+        self.registers.ccr.set(ConditionCode::V, false);
+        self.registers.ccr.set(ConditionCode::C, false);
+    }
 
-        let (result, mut flags) = destination_value.or_cc(size, value);
+    fn divu(&mut self, bus: &mut impl MappedHardware, source: AddressingMode, register: usize) {
+        let divisor: u16 = self
+            .read_addressing_mode(bus, &DataSize::Word, &source)
+            .into();
+        if self.is_aborted() {
+            return;
+        }
+        if divisor == 0 {
+            self.registers.ccr.set(ConditionCode::C, false);
+            self.raise_exception(Exception::ZeroDivide);
+            return;
+        }
+        let dividend = self.registers.data(register);
+        bus.tick(divu_cycles(dividend, divisor) - 4);
+
+        let quotient = dividend / divisor as u32;
+        let remainder = dividend % divisor as u32;
+        if quotient > 0xffff {
+            self.registers.ccr.set(ConditionCode::V, true);
+            self.registers.ccr.set(ConditionCode::C, false);
+            return;
+        }
 
-        self.write_addressing_mode(bus, &size, &destination, result);
-        flags.set(
-            ConditionCode::X,
-            self.registers.ccr.contains(ConditionCode::X),
-        );
-        self.registers.ccr = flags;
+        self.registers
+            .set_data(register, remainder << 16 | quotient);
+        self.set_divide_flags(quotient as u16);
     }
 
-    fn eor(
-        &mut self,
+    fn divs(&mut self, bus: &mut impl MappedHardware, source: AddressingMode, register: usize) {
+        let divisor: u16 = self
+            .read_addressing_mode(bus, &DataSize::Word, &source)
+            .into();
+        if self.is_aborted() {
+            return;
+        }
+        if divisor == 0 {
+            self.registers.ccr.set(ConditionCode::C, false);
+            self.raise_exception(Exception::ZeroDivide);
+            return;
+        }
+        let dividend = self.registers.data(register) as i32;
+        let divisor = divisor as i16;
+        bus.tick(divs_cycles(dividend, divisor) - 4);
+
+        // i64 keeps 0x8000_0000 / -1 from overflowing the host arithmetic
+        let quotient = dividend as i64 / divisor as i64;
+        let remainder = dividend as i64 % divisor as i64;
+        if quotient < i16::MIN as i64 || quotient > i16::MAX as i64 {
+            self.registers.ccr.set(ConditionCode::V, true);
+            self.registers.ccr.set(ConditionCode::C, false);
+            return;
+        }
+
+        let result = (remainder as u16 as u32) << 16 | quotient as u16 as u32;
+        self.registers.set_data(register, result);
+        self.set_divide_flags(quotient as u16);
+    }
+
+    fn set_divide_flags(&mut self, quotient: u16) {
+        self.registers
+            .ccr
+            .set(ConditionCode::N, quotient & 0x8000 != 0);
+        self.registers.ccr.set(ConditionCode::Z, quotient == 0);
+        self.registers.ccr.set(ConditionCode::V, false);
+        self.registers.ccr.set(ConditionCode::C, false);
+    }
+
+    fn addx(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        source: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &size, &source);
+        self.read_modify_write(bus, &size, &destination, |cpu, destination_value| {
+            let (result, flags) = destination_value.addx_cc(size, value, cpu.registers.ccr);
+            cpu.registers.ccr = flags;
+            result
+        });
+    }
+
+    fn subx(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        source: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &size, &source);
+        self.read_modify_write(bus, &size, &destination, |cpu, destination_value| {
+            let (result, flags) = destination_value.subx_cc(size, value, cpu.registers.ccr);
+            cpu.registers.ccr = flags;
+            result
+        });
+    }
+
+    fn neg(&mut self, bus: &mut impl MappedHardware, size: DataSize, ea: AddressingMode) {
+        self.read_modify_write(bus, &size, &ea, |cpu, value| {
+            let (result, flags) = value.neg_cc(size);
+            cpu.registers.ccr = flags;
+            result
+        });
+    }
+
+    fn negx(&mut self, bus: &mut impl MappedHardware, size: DataSize, ea: AddressingMode) {
+        self.read_modify_write(bus, &size, &ea, |cpu, value| {
+            let (result, flags) = value.negx_cc(size, cpu.registers.ccr);
+            cpu.registers.ccr = flags;
+            result
+        });
+    }
+
+    fn abcd(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        source: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &DataSize::Byte, &source);
+        self.read_modify_write(
+            bus,
+            &DataSize::Byte,
+            &destination,
+            |cpu, destination_value| {
+                let (result, flags) = destination_value.abcd_cc(value, cpu.registers.ccr);
+                cpu.registers.ccr = flags;
+                result
+            },
+        );
+    }
+
+    fn sbcd(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        source: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &DataSize::Byte, &source);
+        self.read_modify_write(
+            bus,
+            &DataSize::Byte,
+            &destination,
+            |cpu, destination_value| {
+                let (result, flags) = destination_value.sbcd_cc(value, cpu.registers.ccr);
+                cpu.registers.ccr = flags;
+                result
+            },
+        );
+    }
+
+    fn nbcd(&mut self, bus: &mut impl MappedHardware, ea: AddressingMode) {
+        self.read_modify_write(bus, &DataSize::Byte, &ea, |cpu, value| {
+            let (result, flags) = value.nbcd_cc(cpu.registers.ccr);
+            cpu.registers.ccr = flags;
+            result
+        });
+    }
+
+    fn sub(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        value: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &size, &value);
+        let destination_value = self.read_addressing_mode(bus, &size, &destination);
+
+        let (result, flags) = destination_value.sub_cc(size, value);
+
+        self.write_addressing_mode(bus, &size, &destination, result);
+        if self.is_aborted() {
+            return;
+        }
+        self.registers.ccr = flags;
+    }
+
+    fn or(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        value: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let value = self.read_addressing_mode(bus, &size, &value);
+        let destination_value = self.read_addressing_mode(bus, &size, &destination);
+
+        let (result, mut flags) = destination_value.or_cc(size, value);
+
+        self.write_addressing_mode(bus, &size, &destination, result);
+        if self.is_aborted() {
+            return;
+        }
+        flags.set(
+            ConditionCode::X,
+            self.registers.ccr.contains(ConditionCode::X),
+        );
+        self.registers.ccr = flags;
+    }
+
+    fn eor(
+        &mut self,
         bus: &mut impl MappedHardware,
         size: DataSize,
         value: AddressingMode,
         destination: AddressingMode,
     ) {
-        let destination = match destination {
-            AddressingMode::Immediate => AddressingMode::CCR,
-            _ => destination,
-        };
         let value = self.read_addressing_mode(bus, &size, &value);
         let destination_value = self.read_addressing_mode(bus, &size, &destination);
 
         let (result, mut flags) = destination_value.eor_cc(size, value);
 
         self.write_addressing_mode(bus, &size, &destination, result);
+        if self.is_aborted() {
+            return;
+        }
         flags.set(
             ConditionCode::X,
             self.registers.ccr.contains(ConditionCode::X),
@@ -454,6 +1244,27 @@ impl Cpu {
         self.registers.ccr = flags;
     }
 
+    /// ANDI, ORI and EORI to the CCR or, in supervisor mode, to the whole
+    /// SR. A change of S or M switches the active stack pointer.
+    fn logic_to_status(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        register: AddressingMode,
+        operation: fn(u16, u16) -> u16,
+    ) {
+        let size = match register {
+            AddressingMode::SR => DataSize::Word,
+            _ => DataSize::Byte,
+        };
+        let value: u16 = self.read_immediate(bus, &size).into();
+        if self.is_aborted() {
+            return;
+        }
+        let status: u16 = self.read_addressing_mode(bus, &size, &register).into();
+        let result = Value::from_raw(size, operation(status, value) as u32);
+        self.write_addressing_mode(bus, &size, &register, result);
+    }
+
     fn lea(
         &mut self,
         bus: &mut impl MappedHardware,
@@ -479,6 +1290,46 @@ impl Cpu {
         self.write_addressing_mode(bus, &size, &dest, val);
     }
 
+    /// Moves a word or long word between a data register and every other
+    /// byte of memory, high byte first, so that a peripheral on one byte
+    /// lane sees only byte accesses.
+    fn movep(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        source: AddressingMode,
+        destination: AddressingMode,
+    ) {
+        let bytes = match size {
+            DataSize::LongWord => 4,
+            _ => 2,
+        };
+
+        match (&source, &destination) {
+            (&AddressingMode::AddressIndirectDisplacement(_), &AddressingMode::DataDirect(_)) => {
+                let address = read_addressing_mode_address(self, bus, &size, &source);
+                let mut value = 0;
+                for i in 0..bytes {
+                    let byte: u8 = self
+                        .read_memory(bus, &DataSize::Byte, address.wrapping_add(i * 2))
+                        .into();
+                    value = value << 8 | byte as u32;
+                }
+                let value = Value::from_raw(size, value);
+                self.write_addressing_mode(bus, &size, &destination, value);
+            }
+            (&AddressingMode::DataDirect(reg), &AddressingMode::AddressIndirectDisplacement(_)) => {
+                let value = self.registers.data(reg);
+                let address = read_addressing_mode_address(self, bus, &size, &destination);
+                for i in 0..bytes {
+                    let byte = Value::Byte((value >> ((bytes - 1 - i) * 8)) as u8);
+                    self.write_memory(bus, &DataSize::Byte, address.wrapping_add(i * 2), byte);
+                }
+            }
+            _ => self.raise_exception(Exception::IllegalInstruction),
+        }
+    }
+
     fn movem(
         &mut self,
         bus: &mut impl MappedHardware,
@@ -587,17 +1438,23 @@ impl Cpu {
         let dest_val: u32 = self.read_addressing_mode(bus, &size, &dest).into();
         let result: u32 = dest_val & val;
         let result_value = Value::from_raw(size, result);
+        self.write_addressing_mode(bus, &size, &dest, result_value);
+        if self.is_aborted() {
+            return;
+        }
         self.registers
             .ccr
             .set(ConditionCode::N, is_negative(&size, result_value));
         self.registers.ccr.remove(ConditionCode::V);
         self.registers.ccr.remove(ConditionCode::C);
-        self.write_addressing_mode(bus, &size, &dest, result_value.into());
     }
 
     fn tst(&mut self, bus: &mut impl MappedHardware, size: DataSize, ea: AddressingMode) {
         let value = self.read_addressing_mode(bus, &size, &ea);
         // let value = read_memory(bus, size, address);
+        if self.is_aborted() {
+            return;
+        }
 
         self.registers.ccr.remove(ConditionCode::V);
         self.registers.ccr.remove(ConditionCode::C);
@@ -610,6 +1467,28 @@ impl Cpu {
             .set(ConditionCode::N, 0i32 > value.into());
     }
 
+    /// Reads the bit number of a bit instruction, which is taken modulo 32
+    /// for a data register and modulo 8 for a byte in memory.
+    fn read_bit_number(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        bit: &AddressingMode,
+    ) -> u32 {
+        let bit: u32 = match bit {
+            AddressingMode::Immediate => self.read_immediate(bus, &DataSize::Byte).into(),
+            _ => self
+                .read_addressing_mode(bus, &DataSize::LongWord, bit)
+                .into(),
+        };
+        self.immediate = None;
+
+        match size {
+            DataSize::LongWord => bit % 32,
+            _ => bit % 8,
+        }
+    }
+
     fn btst(
         &mut self,
         bus: &mut impl MappedHardware,
@@ -617,15 +1496,37 @@ impl Cpu {
         bit: AddressingMode,
         ea: AddressingMode,
     ) {
-        let bit: u32 = self.read_addressing_mode(bus, &size, &bit).into();
-        self.immediate = None;
+        let bit = self.read_bit_number(bus, size, &bit);
         let ea: u32 = self.read_addressing_mode(bus, &size, &ea).into();
+        if self.is_aborted() {
+            return;
+        }
 
         self.registers
             .ccr
             .set(ConditionCode::Z, ea & (1 << bit) == 0);
     }
 
+    fn bchg(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        bit: AddressingMode,
+        ea: AddressingMode,
+    ) {
+        self.change_bit(bus, size, bit, ea, |value, mask| value ^ mask);
+    }
+
+    fn bclr(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        bit: AddressingMode,
+        ea: AddressingMode,
+    ) {
+        self.change_bit(bus, size, bit, ea, |value, mask| value & !mask);
+    }
+
     fn bset(
         &mut self,
         bus: &mut impl MappedHardware,
@@ -633,15 +1534,32 @@ impl Cpu {
         bit: AddressingMode,
         ea: AddressingMode,
     ) {
-        let bit: u32 = self.read_addressing_mode(bus, &size, &bit).into();
-        self.immediate = None;
-        let ea: u32 = self.read_addressing_mode(bus, &size, &ea).into();
+        self.change_bit(bus, size, bit, ea, |value, mask| value | mask);
+    }
 
-        self.registers.ccr.set(ConditionCode::Z, ea & bit == 0);
+    /// Tests a bit into Z and writes back the operand as changed by
+    /// `operation`, which is given the operand and a mask of the bit.
+    fn change_bit(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        bit: AddressingMode,
+        ea: AddressingMode,
+        operation: fn(u32, u32) -> u32,
+    ) {
+        let mask = 1 << self.read_bit_number(bus, size, &bit);
+        self.read_modify_write(bus, &size, &ea, |cpu, value| {
+            let value: u32 = value.into();
+            cpu.registers.ccr.set(ConditionCode::Z, value & mask == 0);
+            Value::from_raw(size, operation(value, mask))
+        });
     }
 
     fn clr(&mut self, bus: &mut impl MappedHardware, size: DataSize, destination: AddressingMode) {
         self.write_addressing_mode(bus, &size, &destination, Value::from_raw(size, 0));
+        if self.is_aborted() {
+            return;
+        }
 
         self.registers.ccr.set(ConditionCode::N, false);
         self.registers.ccr.set(ConditionCode::Z, true);
@@ -656,17 +1574,80 @@ impl Cpu {
         source: AddressingMode,
         dest: AddressingMode,
     ) {
-        let val: u32 = self.read_addressing_mode(bus, &size, &source).into();
+        let val = self.read_addressing_mode(bus, &size, &source);
         self.immediate = None;
-        let dest_val: u32 = self.read_addressing_mode(bus, &size, &dest).into();
-        let (result, v) = dest_val.overflowing_sub(val);
-        let result_value = Value::from_raw(size, result);
-        self.registers
-            .ccr
-            .set(ConditionCode::N, is_negative(&size, result_value));
-        self.registers.ccr.set(ConditionCode::Z, result == 0);
-        self.registers.ccr.set(ConditionCode::V, v);
-        self.registers.ccr.set(ConditionCode::C, v);
+        let dest_val = self.read_addressing_mode(bus, &size, &dest);
+        if self.is_aborted() {
+            return;
+        }
+
+        let x = self.registers.ccr & ConditionCode::X;
+        self.registers.ccr = dest_val.cmp_cc(size, val) | x;
+    }
+
+    /// Compares an address register with a source operand, sign-extended
+    /// to 32 bits when it is a word.
+    fn cmpa(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        source: AddressingMode,
+        dest: AddressingMode,
+    ) {
+        let val = self.read_address_operand(bus, size, &source);
+        let dest_val = self.read_addressing_mode(bus, &DataSize::LongWord, &dest);
+        if self.is_aborted() {
+            return;
+        }
+
+        let x = self.registers.ccr & ConditionCode::X;
+        self.registers.ccr = dest_val.cmp_cc(DataSize::LongWord, Value::LongWord(val)) | x;
+    }
+
+    /// Adds to all 32 bits of an address register. Unlike ADD, the
+    /// condition codes are left alone.
+    fn adda(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        value: AddressingMode,
+        register: usize,
+    ) {
+        let value = self.read_address_operand(bus, size, &value);
+        if self.is_aborted() {
+            return;
+        }
+        let address = self.registers.address(register).wrapping_add(value);
+        self.registers.set_address(register, address);
+    }
+
+    fn suba(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        value: AddressingMode,
+        register: usize,
+    ) {
+        let value = self.read_address_operand(bus, size, &value);
+        if self.is_aborted() {
+            return;
+        }
+        let address = self.registers.address(register).wrapping_sub(value);
+        self.registers.set_address(register, address);
+    }
+
+    /// Reads the source operand of an address register operation. Word
+    /// operands are sign-extended to 32 bits.
+    fn read_address_operand(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        size: DataSize,
+        source: &AddressingMode,
+    ) -> u32 {
+        match self.read_addressing_mode(bus, &size, source) {
+            Value::Word(value) => value as i16 as u32,
+            value => value.into(),
+        }
     }
 
     fn link(
@@ -677,14 +1658,24 @@ impl Cpu {
         displacement: AddressingMode,
     ) {
         let size = DataSize::LongWord;
-        let val = read_addressing_mode_address(self, bus, &size, &reg);
-        self.push_stack(bus, size, Value::LongWord(val));
+        let val = self.read_addressing_mode(bus, &size, &reg);
+        self.push_stack(bus, size, val);
         let sp = self.registers.sp();
         self.write_addressing_mode(bus, &size, &reg, Value::LongWord(sp));
         let displacement_val = self.read_addressing_mode(bus, &displacement_size, &displacement);
         self.registers.displace_sp(displacement_val);
     }
 
+    fn unlk(&mut self, bus: &mut impl MappedHardware, reg: usize) {
+        let frame = self.registers.address(reg);
+        self.registers.set_sp(frame);
+        let saved = self.pop_stack(bus, DataSize::LongWord);
+        if self.is_aborted() {
+            return;
+        }
+        self.registers.set_address(reg, saved.into());
+    }
+
     fn pea(&mut self, bus: &mut impl MappedHardware, ea: AddressingMode) {
         let val = read_addressing_mode_address(self, bus, &DataSize::LongWord, &ea);
         self.push_stack(bus, DataSize::LongWord, Value::LongWord(val));
@@ -704,395 +1695,296 @@ impl Cpu {
         self.registers.set_data(register, new_val);
     }
 
-    fn not(&mut self, bus: &mut impl MappedHardware, size: DataSize, ea: AddressingMode) {
-        let val: u32 = self.read_addressing_mode(bus, &size, &ea).into();
+    fn exg(
+        &mut self,
+        bus: &mut impl MappedHardware,
+        first: AddressingMode,
+        second: AddressingMode,
+    ) {
+        let first_value = self.read_addressing_mode(bus, &DataSize::LongWord, &first);
+        let second_value = self.read_addressing_mode(bus, &DataSize::LongWord, &second);
+        self.write_addressing_mode(bus, &DataSize::LongWord, &first, second_value);
+        self.write_addressing_mode(bus, &DataSize::LongWord, &second, first_value);
+    }
+
+    /// Sign-extends the low byte of a data register to a word, or the low
+    /// word to a long word.
+    fn ext(&mut self, size: DataSize, register: usize) {
+        let val = self.registers.data(register);
+        let new_val = match size {
+            DataSize::LongWord => val as u16 as i16 as i32 as u32,
+            _ => val & 0xffff_0000 | val as u8 as i8 as i16 as u16 as u32,
+        };
+        let result = Value::from_raw(size, new_val);
 
-        let val = Value::from_raw(size, !val);
         self.registers
             .ccr
-            .set(ConditionCode::Z, is_zero(&size, val));
+            .set(ConditionCode::N, is_negative(&size, result));
         self.registers
             .ccr
-            .set(ConditionCode::N, is_negative(&size, val));
+            .set(ConditionCode::Z, is_zero(&size, result));
         self.registers.ccr.remove(ConditionCode::V);
         self.registers.ccr.remove(ConditionCode::C);
-        self.write_addressing_mode(bus, &size, &ea, val);
+        self.registers.set_data(register, new_val);
     }
 
-    fn lsl(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        count: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let count: u32 = match count {
-            AddressingMode::DataDirect(_) => {
-                let c: u32 = self
-                    .read_addressing_mode(bus, &DataSize::LongWord, &count)
-                    .into();
-                c % 64
-            }
-            _ => {
-                let mut c: u32 = self.read_addressing_mode(bus, &size, &count).into();
-                if c == 0 {
-                    c = 8
-                }
-                c
-            }
-        };
-        let dest_val = self.read_addressing_mode(bus, &size, &destination);
-
-        let (result, mut ccr) = shift_left(size, count, dest_val);
-
-        if count == 0 {
-            ccr.set(
-                ConditionCode::X,
-                self.registers.ccr.contains(ConditionCode::X),
-            );
+    /// Tests a byte and sets its high bit. In memory this is a single locked
+    /// read-modify-write cycle, so it can be used as a semaphore between
+    /// bus masters.
+    fn tas(&mut self, bus: &mut impl MappedHardware, ea: AddressingMode) {
+        let locked = !matches!(ea, AddressingMode::DataDirect(_));
+        if locked {
+            bus.set_locked(true);
         }
-
-        self.registers.set_ccr(ccr.bits());
-        self.write_addressing_mode(bus, &size, &destination, result);
-    }
-
-    fn lsr(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        count: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let count: u32 = match count {
-            AddressingMode::DataDirect(_) => {
-                let c: u32 = self
-                    .read_addressing_mode(bus, &DataSize::LongWord, &count)
-                    .into();
-                c % 64
-            }
-            _ => {
-                let mut c: u32 = self.read_addressing_mode(bus, &size, &count).into();
-                if c == 0 {
-                    c = 8
-                }
-                c
-            }
-        };
-        let dest_val = self.read_addressing_mode(bus, &size, &destination);
-
-        let (result, mut ccr) = shift_right(size, count, dest_val);
-
-        if count == 0 {
-            ccr.set(
-                ConditionCode::X,
-                self.registers.ccr.contains(ConditionCode::X),
-            );
+        self.read_modify_write(bus, &DataSize::Byte, &ea, |cpu, value| {
+            let value: u8 = value.into();
+            cpu.registers.ccr.set(ConditionCode::N, value & 0x80 != 0);
+            cpu.registers.ccr.set(ConditionCode::Z, value == 0);
+            cpu.registers.ccr.remove(ConditionCode::V);
+            cpu.registers.ccr.remove(ConditionCode::C);
+            Value::Byte(value | 0x80)
+        });
+        if locked {
+            bus.set_locked(false);
         }
-
-        self.registers.set_ccr(ccr.bits());
-        self.write_addressing_mode(bus, &size, &destination, result);
     }
 
-    fn asl(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        count: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let count: u32 = match count {
-            AddressingMode::DataDirect(_) => {
-                let c: u32 = self
-                    .read_addressing_mode(bus, &DataSize::LongWord, &count)
-                    .into();
-                c % 64
-            }
-            _ => {
-                let mut c: u32 = self.read_addressing_mode(bus, &size, &count).into();
-                if c == 0 {
-                    c = 8
-                }
-                c
-            }
+    /// Sets a byte to all ones if the condition is true and to zero
+    /// otherwise. Like the 68000, the byte is read before it is written.
+    fn scc(&mut self, bus: &mut impl MappedHardware, condition: Condition, ea: AddressingMode) {
+        let result = if self.read_condition_code(condition) {
+            0xff
+        } else {
+            0x00
         };
-        let dest_val = self.read_addressing_mode(bus, &size, &destination);
-
-        let msb = match size {
-            DataSize::Byte => {
-                let v: u8 = dest_val.into();
-                v & 0x80 != 0
-            }
-            DataSize::Word => {
-                let v: u16 = dest_val.into();
-                v & 0x8000 != 0
-            }
-            DataSize::LongWord => {
-                let v: u32 = dest_val.into();
-                v & 0x8000_0000 != 0
-            }
-        };
-
-        let (result, mut ccr) = shift_left(size, count, dest_val);
+        self.read_modify_write(bus, &DataSize::Byte, &ea, |_, _| Value::Byte(result));
+    }
 
-        let mut val: u32 = result.into();
+    fn not(&mut self, bus: &mut impl MappedHardware, size: DataSize, ea: AddressingMode) {
+        let val: u32 = self.read_addressing_mode(bus, &size, &ea).into();
 
-        if count == 0 {
-            ccr.set(
-                ConditionCode::X,
-                self.registers.ccr.contains(ConditionCode::X),
-            );
+        let val = Value::from_raw(size, !val);
+        self.write_addressing_mode(bus, &size, &ea, val);
+        if self.is_aborted() {
+            return;
         }
-
-        self.registers.set_ccr(ccr.bits());
-        self.write_addressing_mode(bus, &size, &destination, Value::from_raw(size, val));
+        self.registers
+            .ccr
+            .set(ConditionCode::Z, is_zero(&size, val));
+        self.registers
+            .ccr
+            .set(ConditionCode::N, is_negative(&size, val));
+        self.registers.ccr.remove(ConditionCode::V);
+        self.registers.ccr.remove(ConditionCode::C);
     }
 
-    fn asr(
+    /// Shifts or rotates a data register by an immediate count (where 0
+    /// stands for 8) or by a register count modulo 64, or a word in memory
+    /// by one bit.
+    fn shift(
         &mut self,
         bus: &mut impl MappedHardware,
+        shift: Shift,
         size: DataSize,
         count: AddressingMode,
         destination: AddressingMode,
     ) {
-        let count: u32 = match count {
-            AddressingMode::DataDirect(_) => {
-                let c: u32 = self
+        let count = match count {
+            AddressingMode::Value(0) => 8,
+            AddressingMode::Value(count) => count,
+            _ => {
+                let count: u32 = self
                     .read_addressing_mode(bus, &DataSize::LongWord, &count)
                     .into();
-                c % 64
-            }
-            _ => {
-                let mut c: u32 = self.read_addressing_mode(bus, &size, &count).into();
-                if c == 0 {
-                    c = 8
-                }
-                c
+                count % 64
             }
         };
-        let mut dest_val = self.read_addressing_mode(bus, &size, &destination);
-        let pre_msb = is_negative(&size, dest_val);
-        let mut bit = 0;
-        for i in 0..count {
-            let msb = match size {
-                DataSize::Byte => {
-                    let mut v: u8 = dest_val.into();
-                    let msb = v & 0x80;
-                    bit = (v & 0b1) as u8;
-                    v = v >> 1;
-                    v &= msb;
-                    dest_val = Value::from_raw(size, v as u32)
-                }
-                DataSize::Word => {
-                    let mut v: u16 = dest_val.into();
-                    let msb = v & 0x8000;
-                    bit = (v & 0b1) as u8;
-                    v = v >> 1;
-                    v &= msb;
-                    dest_val = Value::from_raw(size, v as u32)
-                }
-                DataSize::LongWord => {
-                    let mut v: u32 = dest_val.into();
-                    let msb = v & 0x8000_0000;
-                    bit = (v & 0b1) as u8;
-                    v = v >> 1;
-                    v &= msb;
-                    dest_val = Value::from_raw(size, v as u32)
-                }
-            };
-        }
-
-        if count != 0 {
-            self.registers.ccr.set(ConditionCode::X, bit == 1);
-        }
-        self.registers.ccr.set(ConditionCode::C, bit == 1);
-        self.registers
-            .ccr
-            .set(ConditionCode::N, is_negative(&size, dest_val));
-        self.registers
-            .ccr
-            .set(ConditionCode::Z, is_zero(&size, dest_val));
-        self.registers
-            .ccr
-            .set(ConditionCode::V, pre_msb != is_negative(&size, dest_val));
 
-        self.write_addressing_mode(bus, &size, &destination, dest_val);
+        self.read_modify_write(bus, &size, &destination, |cpu, value| {
+            let (result, ccr) = shift.apply(size, count, value, cpu.registers.ccr);
+            cpu.registers.ccr = ccr;
+            result
+        });
     }
+}
 
-    fn rol(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        count: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let (res, ccr) = rotate(self, bus, size, count, &destination, true);
-        self.write_addressing_mode(bus, &size, &destination, res);
-        self.registers.set_ccr(ccr.bits());
+/// Instructions that may only be executed in supervisor mode.
+fn is_privileged(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::RESET | Instruction::STOP(_) | Instruction::RTE => true,
+        Instruction::MOVE(_, _, AddressingMode::SR) => true,
+        Instruction::MOVE(_, AddressingMode::USP, _)
+        | Instruction::MOVE(_, _, AddressingMode::USP) => true,
+        Instruction::ORI(_, _, AddressingMode::SR)
+        | Instruction::ANDI(_, _, AddressingMode::SR)
+        | Instruction::EORI(_, _, AddressingMode::SR) => true,
+        _ => false,
     }
+}
 
-    fn ror(
-        &mut self,
-        bus: &mut impl MappedHardware,
-        size: DataSize,
-        count: AddressingMode,
-        destination: AddressingMode,
-    ) {
-        let (res, ccr) = rotate(self, bus, size, count, &destination, false);
-        self.write_addressing_mode(bus, &size, &destination, res);
-        self.registers.set_ccr(ccr.bits());
+/// Cycles taken by DIVU, including the opcode fetch but not the effective
+/// address. The 68000 runs a restoring division and spends an extra cycle
+/// on every quotient bit that doesn't need a subtraction after the shift.
+fn divu_cycles(dividend: u32, divisor: u16) -> usize {
+    if dividend >> 16 >= divisor as u32 {
+        return 10;
     }
-}
 
-fn rotate(
-    cpu: &mut Cpu,
-    bus: &mut impl MappedHardware,
-    size: DataSize,
-    count: AddressingMode,
-    destination: &AddressingMode,
-    rotate_left: bool,
-) -> (Value, ConditionCode) {
-    let count: u32 = match count {
-        AddressingMode::DataDirect(_) => {
-            let c: u32 = read_addressing_mode(cpu, bus, &DataSize::LongWord, &count).into();
-            c % 64
-        }
-        _ => {
-            let mut c: u32 = read_addressing_mode(cpu, bus, &size, &count).into();
-            if c == 0 {
-                c = 8
-            }
-            c
-        }
-    };
-
-    cpu.immediate = None;
-    let value = read_addressing_mode(cpu, bus, &size, &destination);
-    let (result, bit) = match size {
-        DataSize::Byte => {
-            let mut value: u8 = value.into();
-            if rotate_left {
-                value = value.rotate_left(count);
-                (Value::Byte(value), value & 0b1 == 0b1)
-            } else {
-                value = value.rotate_right(count);
-                (Value::Byte(value), value & 0x80 == 0x80)
-            }
-        }
-        DataSize::Word => {
-            let mut value: u16 = value.into();
-            if rotate_left {
-                value = value.rotate_left(count);
-                (Value::Word(value), value & 0b1 == 0b1)
-            } else {
-                value = value.rotate_right(count);
-                (Value::Word(value), value & 0x8000 == 0x8000)
+    let mut cycles = 38;
+    let divisor = (divisor as u32) << 16;
+    let mut dividend = dividend;
+    for _ in 0..15 {
+        let carry = dividend & 0x8000_0000 != 0;
+        dividend <<= 1;
+        if carry {
+            dividend = dividend.wrapping_sub(divisor);
+        } else {
+            cycles += 2;
+            if dividend >= divisor {
+                dividend -= divisor;
+                cycles -= 1;
             }
         }
-        DataSize::LongWord => {
-            let mut value: u32 = value.into();
-            if rotate_left {
-                value = value.rotate_left(count);
-                (Value::LongWord(value), value & 0b1 == 0b1)
-            } else {
-                value = value.rotate_right(count);
-                (Value::LongWord(value), value & 0x8000_0000 == 0x8000_0000)
-            }
-        }
-    };
-
-    let mut ccr = ConditionCode::empty();
-
-    ccr.set(
-        ConditionCode::X,
-        cpu.registers.ccr.contains(ConditionCode::X),
-    );
-
-    ccr.set(ConditionCode::N, is_negative(&size, result));
-    ccr.set(ConditionCode::Z, is_zero(&size, result));
-    if bit {
-        if count > 0 {
-            ccr |= ConditionCode::C;
-        }
     }
-
-    (result, ccr)
+    cycles * 2
 }
 
-fn shift_right(size: DataSize, count: u32, value: Value) -> (Value, ConditionCode) {
-    let (result, bit) = match size {
-        DataSize::Byte => {
-            let value: u8 = value.into();
-            let b = (value >> (count - 1)) & 0x1 != 0;
-            (Value::Byte(value >> count), b)
-        }
-        DataSize::Word => {
-            let value: u16 = value.into();
-            let b = (value >> (count - 1)) & 0x1 != 0;
-            (Value::Word(value >> count), b)
-        }
-        DataSize::LongWord => {
-            let value: u32 = value.into();
-            let b = (value >> (count - 1)) & 0x1 != 0;
-            (Value::LongWord(value >> count), b)
-        }
-    };
-
-    let mut ccr = ConditionCode::empty();
-
-    ccr.set(ConditionCode::N, is_negative(&size, result));
-    ccr.set(ConditionCode::Z, is_zero(&size, result));
-    if bit {
-        ccr |= ConditionCode::X;
-        if count > 0 {
-            ccr |= ConditionCode::C;
-        }
-        // ccr |=
+/// Cycles taken by DIVS, including the opcode fetch but not the effective
+/// address. They depend on the signs of the operands and on the bits of
+/// the absolute quotient.
+fn divs_cycles(dividend: i32, divisor: i16) -> usize {
+    let mut cycles = 6;
+    if dividend < 0 {
+        cycles += 1;
     }
 
-    (result, ccr)
-}
+    let absolute_dividend = dividend.unsigned_abs();
+    let absolute_divisor = divisor.unsigned_abs() as u32;
+    if absolute_dividend >> 16 >= absolute_divisor {
+        return (cycles + 2) * 2;
+    }
 
-fn shift_left(size: DataSize, count: u32, value: Value) -> (Value, ConditionCode) {
-    let (result, bit) = match size {
-        DataSize::Byte => {
-            let value: u8 = value.into();
-            let b = (value << (count - 1)) & 0x80 != 0;
-            (Value::Byte(value << count), b)
+    let mut quotient = absolute_dividend / absolute_divisor;
+    cycles += 55;
+    if divisor >= 0 {
+        if dividend >= 0 {
+            cycles -= 1;
+        } else {
+            cycles += 1;
         }
-        DataSize::Word => {
-            let value: u16 = value.into();
-            let b = (value << (count - 1)) & 0x8000 != 0;
-            (Value::Word(value << count), b)
-        }
-        DataSize::LongWord => {
-            let value: u32 = value.into();
-            let b = (value << (count - 1)) & 0x8000_0000 != 0;
-            (Value::LongWord(value << count), b)
-        }
-    };
-
-    let mut ccr = ConditionCode::empty();
-
-    ccr.set(ConditionCode::N, is_negative(&size, result));
-    ccr.set(ConditionCode::Z, is_zero(&size, result));
-    if bit {
-        ccr |= ConditionCode::X;
-        if count > 0 {
-            ccr |= ConditionCode::C;
+    }
+    for _ in 0..15 {
+        if quotient & 0x8000 == 0 {
+            cycles += 1;
         }
-        // ccr |=
+        quotient <<= 1;
     }
+    cycles * 2
+}
 
-    (result, ccr)
+#[derive(Clone, Copy)]
+enum Shift {
+    ArithmeticLeft,
+    ArithmeticRight,
+    LogicalLeft,
+    LogicalRight,
+    RotateLeft,
+    RotateRight,
+    RotateExtendLeft,
+    RotateExtendRight,
 }
 
-fn read_memory(bus: &mut impl MappedHardware, size: DataSize, address: Value) -> Value {
-    let size2 = size.clone();
-    match size {
-        DataSize::Byte => Value::Byte(bus.read_byte(address.into()).unwrap()),
-        DataSize::Word => Value::Word(bus.read_word(address.into()).unwrap()),
-        DataSize::LongWord => Value::LongWord(bus.read_long(address.into()).unwrap()),
-        _ => unreachable!("{:?} {:?}", size2, address),
+impl Shift {
+    /// Shifts `value` by `count` bits, returning the result and the new
+    /// condition codes. A count of zero only sets N and Z, clears V and
+    /// C, and leaves X alone, except for ROXL and ROXR which copy X to C.
+    fn apply(
+        self,
+        size: DataSize,
+        count: u32,
+        value: Value,
+        ccr: ConditionCode,
+    ) -> (Value, ConditionCode) {
+        let width = match size {
+            DataSize::Byte => 8,
+            DataSize::Word => 16,
+            DataSize::LongWord => 32,
+        };
+        let mask = (1u64 << width) - 1;
+        let sign = 1u64 << (width - 1);
+        let value: u32 = value.into();
+        let value = value as u64 & mask;
+        let x = ccr.contains(ConditionCode::X);
+
+        // the last bit shifted out, if any bits were shifted at all
+        let shifted_out = |bit: u32| count > 0 && bit < width && value & 1 << bit != 0;
+
+        let (result, carry, extend, overflow) = match self {
+            Shift::LogicalLeft | Shift::ArithmeticLeft => {
+                let result = (value << count.min(width)) & mask;
+                let carry = count <= width && shifted_out(width - count);
+                // ASL overflows if the sign bit changes at any point
+                let overflow = match self {
+                    Shift::ArithmeticLeft if count >= width => value != 0,
+                    Shift::ArithmeticLeft => {
+                        let top = value >> (width - 1 - count);
+                        top != 0 && top != (1 << (count + 1)) - 1
+                    }
+                    _ => false,
+                };
+                (result, carry, Some(carry), overflow)
+            }
+            Shift::LogicalRight => {
+                let result = value >> count.min(width);
+                let carry = count <= width && shifted_out(count.wrapping_sub(1));
+                (result, carry, Some(carry), false)
+            }
+            Shift::ArithmeticRight => {
+                let negative = value & sign != 0;
+                let extended = if negative { value | !mask } else { value };
+                let result = ((extended as i64) >> count.min(63)) as u64 & mask;
+                let carry = if count > width {
+                    negative
+                } else {
+                    shifted_out(count.wrapping_sub(1))
+                };
+                (result, carry, Some(carry), false)
+            }
+            Shift::RotateLeft => {
+                let rotate = count % width;
+                let result = (value << rotate | value >> (width - rotate)) & mask;
+                (result, count > 0 && result & 1 != 0, None, false)
+            }
+            Shift::RotateRight => {
+                let rotate = count % width;
+                let result = (value >> rotate | value << (width - rotate)) & mask;
+                (result, count > 0 && result & sign != 0, None, false)
+            }
+            Shift::RotateExtendLeft | Shift::RotateExtendRight => {
+                // X sits above the operand as one more bit of the rotation
+                let rotate = count % (width + 1);
+                let wide_mask = (mask << 1) | 1;
+                let wide = (x as u64) << width | value;
+                let rotated = match self {
+                    Shift::RotateExtendLeft => wide << rotate | wide >> (width + 1 - rotate),
+                    _ => wide >> rotate | wide << (width + 1 - rotate),
+                } & wide_mask;
+                let extend = rotated >> width != 0;
+                (rotated & mask, extend, Some(extend), false)
+            }
+        };
+
+        let mut flags = ConditionCode::empty();
+        flags.set(
+            ConditionCode::X,
+            if count > 0 { extend.unwrap_or(x) } else { x },
+        );
+        flags.set(ConditionCode::N, result & sign != 0);
+        flags.set(ConditionCode::Z, result == 0);
+        flags.set(ConditionCode::V, overflow);
+        flags.set(ConditionCode::C, carry);
+
+        (Value::from_raw(size, result as u32), flags)
     }
 }
 
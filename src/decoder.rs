@@ -2,6 +2,15 @@ use addressing_mode::{decode_addressing_mode, AddressingMode, DataSize, DataSize
 use instruction_set::Instruction;
 
 pub fn decode(opcode: usize) -> Instruction {
+    let instruction = decode_opcode(opcode);
+    if has_valid_operands(&instruction) {
+        instruction
+    } else {
+        Instruction::Illegal
+    }
+}
+
+fn decode_opcode(opcode: usize) -> Instruction {
     let part1 = opcode >> 12;
     let part2 = (opcode >> 6) & 0b111111;
     let part3 = opcode & 0b111111;
@@ -20,31 +29,29 @@ pub fn decode(opcode: usize) -> Instruction {
         (0b0100, 0b111001, 0b110101) => Instruction::RTS,
         (0b0100, 0b111001, 0b110110) => Instruction::TRAPV,
         (0b0100, 0b111001, 0b110111) => Instruction::RTR,
+        (0b0100, 0b101011, 0b111100) => Instruction::Illegal,
 
         (0b0000, _, _) => {
             use addressing_mode::AddressingMode::Immediate;
 
             match part2l {
-                0b100 if part3h != 0b001 => Instruction::BTST(
-                    DataSize::LongWord,
-                    AddressingMode::DataDirect(part2h),
-                    part3.into(),
-                ),
-                0b101 if part3h != 0b001 => Instruction::BCHG(
-                    DataSize::LongWord,
-                    AddressingMode::DataDirect(part2h),
-                    part3.into(),
-                ),
-                0b110 if part3h != 0b001 => Instruction::BCLR(
-                    DataSize::LongWord,
-                    AddressingMode::DataDirect(part2h),
-                    part3.into(),
-                ),
-                0b111 if part3h != 0b001 => Instruction::BSET(
-                    DataSize::LongWord,
-                    AddressingMode::DataDirect(part2h),
-                    part3.into(),
-                ),
+                0b100..=0b111 if part3h != 0b001 => {
+                    // bit numbers are taken modulo 32 for data registers,
+                    // anything else is a byte in memory
+                    let addressing_mode = decode_addressing_mode(part3);
+                    let size = match addressing_mode {
+                        AddressingMode::DataDirect(_) => DataSize::LongWord,
+                        _ => DataSize::Byte,
+                    };
+                    let bit = AddressingMode::DataDirect(part2h);
+                    match part2l {
+                        0b100 => Instruction::BTST(size, bit, addressing_mode),
+                        0b101 => Instruction::BCHG(size, bit, addressing_mode),
+                        0b110 => Instruction::BCLR(size, bit, addressing_mode),
+                        0b111 => Instruction::BSET(size, bit, addressing_mode),
+                        _ => unreachable!(),
+                    }
+                }
 
                 0b100 | 0b101 if part3h == 0b001 => Instruction::MOVEP(
                     DataSizeIdentifier::OneBit(part2l & 0b1).into(),
@@ -74,6 +81,23 @@ pub fn decode(opcode: usize) -> Instruction {
                                 _ => unreachable!(),
                             }
                         }
+                        _ if part2 & 0b11 == 0b11 => Instruction::Illegal,
+                        // an immediate destination selects the CCR for bytes
+                        // and the whole SR for words
+                        0b0000 | 0b0010 | 0b1010 if part3 == 0b111100 => {
+                            let destination = match part2 & 0b11 {
+                                0b00 => AddressingMode::CCR,
+                                0b01 => AddressingMode::SR,
+                                _ => return Instruction::Illegal,
+                            };
+                            let size: DataSize = DataSizeIdentifier::TwoBit(part2 & 0b11).into();
+                            match part2 >> 2 {
+                                0b0000 => Instruction::ORI(size, Immediate, destination),
+                                0b0010 => Instruction::ANDI(size, Immediate, destination),
+                                _ => Instruction::EORI(size, Immediate, destination),
+                            }
+                        }
+                        _ if part3 == 0b111100 => Instruction::Illegal,
                         _ => {
                             let size: DataSize = DataSizeIdentifier::TwoBit(part2 & 0b11).into();
                             match part2 >> 2 {
@@ -83,7 +107,7 @@ pub fn decode(opcode: usize) -> Instruction {
                                 0b0110 => Instruction::ADDI(size, Immediate, part3.into()),
                                 0b1010 => Instruction::EORI(size, Immediate, part3.into()),
                                 0b1100 => Instruction::CMPI(size, part3.into()),
-                                _ => unreachable!(),
+                                _ => Instruction::Illegal,
                             }
                         }
                     }
@@ -109,19 +133,26 @@ pub fn decode(opcode: usize) -> Instruction {
                 AddressingMode::Immediate,
             ),
             (0b11, _) => Instruction::ST(DataSize::Byte, (part2 >> 2).into(), part3.into()),
-            (_, _) => match part2l >> 2 {
-                0b0 => Instruction::ADDQ(
-                    DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
-                    AddressingMode::Value(part2h as u32),
-                    part3.into(),
-                ),
-                0b1 => Instruction::SUBQ(
-                    DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
-                    AddressingMode::Value(part2h as u32),
-                    part3.into(),
-                ),
-                _ => unreachable!(),
-            },
+            (_, _) => {
+                // the quick data 1-8 is encoded with 8 as 0
+                let quick = match part2h {
+                    0 => 8,
+                    data => data as u32,
+                };
+                match part2l >> 2 {
+                    0b0 => Instruction::ADDQ(
+                        DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
+                        AddressingMode::Value(quick),
+                        part3.into(),
+                    ),
+                    0b1 => Instruction::SUBQ(
+                        DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
+                        AddressingMode::Value(quick),
+                        part3.into(),
+                    ),
+                    _ => unreachable!(),
+                }
+            }
         },
         (0b0110, _, _) => {
             // 4 highest bits of part2
@@ -151,17 +182,17 @@ pub fn decode(opcode: usize) -> Instruction {
                 AddressingMode::DataDirect(part2 >> 3),
             ),
             (0b100, 0b000) => Instruction::SBCD(
-                AddressingMode::DataDirect(part2 >> 3),
                 AddressingMode::DataDirect(part3 & 0b111),
+                AddressingMode::DataDirect(part2 >> 3),
             ),
             (0b100, 0b001) => Instruction::SBCD(
-                AddressingMode::DataDirect(part2 >> 3),
-                AddressingMode::DataDirect(part3 & 0b111),
+                AddressingMode::AddressIndirectPreDecrement(part3 & 0b111),
+                AddressingMode::AddressIndirectPreDecrement(part2 >> 3),
             ),
             (0b111, _) => Instruction::DIVS(
                 DataSize::Word,
-                AddressingMode::DataDirect(part2 >> 3),
                 decode_addressing_mode(part3),
+                AddressingMode::DataDirect(part2 >> 3),
             ),
             (_, _) => match (part2 >> 2) & 0b1 {
                 0b0 => Instruction::OR(
@@ -218,11 +249,6 @@ pub fn decode(opcode: usize) -> Instruction {
                 part3.into(),
                 AddressingMode::DataDirect(part2h),
             ),
-            (0b100, _, 0b000) => Instruction::CMPM(
-                DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
-                AddressingMode::DataDirect(part3l),
-                AddressingMode::DataDirect(part2h),
-            ),
             (0b100, _, 0b001) => Instruction::CMPM(
                 DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
                 AddressingMode::AddressIndirectPostIncrement(part3l),
@@ -230,10 +256,10 @@ pub fn decode(opcode: usize) -> Instruction {
             ),
             (0b100, _, _) => Instruction::EOR(
                 DataSizeIdentifier::TwoBit(part2l & 0b11).into(),
+                AddressingMode::DataDirect(part2h),
                 part3.into(),
-                AddressingMode::AddressDirect(part2h),
             ),
-            (_, _, _) => unreachable!(),
+            (_, _, _) => Instruction::Illegal,
         },
         (0b1100, _, _) => match (part2l, part3h) {
             (0b011, _) => Instruction::MULU(
@@ -332,6 +358,7 @@ pub fn decode(opcode: usize) -> Instruction {
                 0b010111 => Instruction::ROXLD(size, value, addressing_mode),
                 0b011011 => Instruction::RORD(size, value, addressing_mode),
                 0b011111 => Instruction::ROLD(size, value, addressing_mode),
+                _ if part2l & 0b11 == 0b11 => Instruction::Illegal,
                 _ => {
                     let size: DataSize = DataSizeIdentifier::TwoBit(part2l & 0b11).into();
                     let value = AddressingMode::Value(part2h as u32);
@@ -360,7 +387,9 @@ pub fn decode(opcode: usize) -> Instruction {
                 }
             }
         }
-        _ => unimplemented!("decode missing for {:04X} {:016b}", opcode, opcode),
+        (0b1010, _, _) => Instruction::LineA(opcode as u16),
+        (0b1111, _, _) => Instruction::LineF(opcode as u16),
+        _ => Instruction::Illegal,
     }
 }
 
@@ -382,6 +411,7 @@ fn decode_0100(opcode: usize) -> Instruction {
                 Instruction::MOVE(DataSize::Word, AddressingMode::SR, part3.into())
             }
             (0b000, _, _) => Instruction::NEGX(two_bit_size.into(), part3.into()),
+            (0b001, 0b011, _) => Instruction::Illegal,
             (0b001, _, _) => Instruction::CLR(two_bit_size.into(), part3.into()),
             (0b010, 0b011, _) => {
                 Instruction::MOVE(DataSize::Word, part3.into(), AddressingMode::CCR)
@@ -410,19 +440,21 @@ fn decode_0100(opcode: usize) -> Instruction {
             ),
             (0b111, 0b001, 0b011) => Instruction::UNLK(AddressingMode::AddressDirect(part3l)),
             (0b111, 0b001, 0b100) => Instruction::MOVE(
-                DataSize::Word,
+                DataSize::LongWord,
                 AddressingMode::AddressDirect(part3l),
                 AddressingMode::USP,
             ),
             (0b111, 0b001, 0b101) => Instruction::MOVE(
-                DataSize::Word,
+                DataSize::LongWord,
                 AddressingMode::USP,
                 AddressingMode::AddressDirect(part3l),
             ),
-            (0b111, 0b001, _) => Instruction::TRAP(AddressingMode::Vector(part3 as u32)),
+            (0b111, 0b001, 0b000) | (0b111, 0b001, 0b001) => {
+                Instruction::TRAP(AddressingMode::Vector(part3 as u32))
+            }
             (0b111, 0b010, _) => Instruction::JSR(part3.into()),
             (0b111, 0b011, _) => Instruction::JMP(part3.into()),
-            _ => unreachable!(),
+            _ => Instruction::Illegal,
         },
         0b1 => match part2l {
             0b110 => Instruction::CHK(
@@ -431,8 +463,157 @@ fn decode_0100(opcode: usize) -> Instruction {
                 AddressingMode::DataDirect(part2h),
             ),
             0b111 => Instruction::LEA(part3.into(), AddressingMode::AddressDirect(part2h)),
-            _ => panic!("{:04X} {:016b}", opcode, opcode),
+            _ => Instruction::Illegal,
         },
         _ => unreachable!(),
     }
 }
+
+/// Any mode that names an operand, except an address register for bytes.
+fn is_source(size: &DataSize, mode: &AddressingMode) -> bool {
+    match *mode {
+        AddressingMode::AddressDirect(_) => *size != DataSize::Byte,
+        _ => is_data(mode),
+    }
+}
+
+fn is_data(mode: &AddressingMode) -> bool {
+    match *mode {
+        AddressingMode::DataDirect(_)
+        | AddressingMode::Immediate
+        | AddressingMode::PCIndirectDisplacementMode
+        | AddressingMode::PCIndirectIndexed => true,
+        _ => is_memory_alterable(mode),
+    }
+}
+
+fn is_data_alterable(mode: &AddressingMode) -> bool {
+    match *mode {
+        AddressingMode::DataDirect(_) => true,
+        _ => is_memory_alterable(mode),
+    }
+}
+
+fn is_memory_alterable(mode: &AddressingMode) -> bool {
+    matches!(
+        *mode,
+        AddressingMode::AddressIndirect(_)
+            | AddressingMode::AddressIndirectPostIncrement(_)
+            | AddressingMode::AddressIndirectPreDecrement(_)
+            | AddressingMode::AddressIndirectDisplacement(_)
+            | AddressingMode::AddressIndirectIndexedAndDisplacement(_)
+            | AddressingMode::AbsoluteAddress(_)
+    )
+}
+
+fn is_control(mode: &AddressingMode) -> bool {
+    matches!(
+        *mode,
+        AddressingMode::AddressIndirect(_)
+            | AddressingMode::AddressIndirectDisplacement(_)
+            | AddressingMode::AddressIndirectIndexedAndDisplacement(_)
+            | AddressingMode::AbsoluteAddress(_)
+            | AddressingMode::PCIndirectDisplacementMode
+            | AddressingMode::PCIndirectIndexed
+    )
+}
+
+/// Checks the effective addresses against the modes each instruction
+/// allows, so that an invalid one traps before anything is executed.
+fn has_valid_operands(instruction: &Instruction) -> bool {
+    use addressing_mode::AddressingMode::*;
+
+    match *instruction {
+        Instruction::ORI(_, _, ref dest)
+        | Instruction::ANDI(_, _, ref dest)
+        | Instruction::EORI(_, _, ref dest) => match *dest {
+            SR | CCR => true,
+            _ => is_data_alterable(dest),
+        },
+        Instruction::SUBI(_, _, ref dest) | Instruction::ADDI(_, _, ref dest) => {
+            is_data_alterable(dest)
+        }
+        Instruction::CMPI(_, ref dest) => is_data_alterable(dest),
+
+        // only the dynamic form may test a bit of an immediate byte
+        Instruction::BTST(_, Immediate, ref dest) => is_data(dest) && *dest != Immediate,
+        Instruction::BTST(_, _, ref dest) => is_data(dest),
+        Instruction::BCHG(_, _, ref dest)
+        | Instruction::BCLR(_, _, ref dest)
+        | Instruction::BSET(_, _, ref dest) => is_data_alterable(dest),
+
+        Instruction::MOVE(_, SR, ref dest) => is_data_alterable(dest),
+        Instruction::MOVE(_, ref source, SR) | Instruction::MOVE(_, ref source, CCR) => {
+            is_data(source)
+        }
+        Instruction::MOVE(_, USP, _) | Instruction::MOVE(_, _, USP) => true,
+        Instruction::MOVE(ref size, ref source, ref dest) => {
+            is_source(size, source)
+                && match *dest {
+                    AddressDirect(_) => *size != DataSize::Byte,
+                    _ => is_data_alterable(dest),
+                }
+        }
+
+        Instruction::ADDA(ref size, ref source, _)
+        | Instruction::SUBA(ref size, ref source, _)
+        | Instruction::CMPA(ref size, ref source, _)
+        | Instruction::CMP(ref size, ref source, _) => is_source(size, source),
+
+        // register-to-memory forms have the register as their source
+        Instruction::ADD(ref size, ref source, DataDirect(_))
+        | Instruction::SUB(ref size, ref source, DataDirect(_)) => is_source(size, source),
+        Instruction::AND(_, ref source, DataDirect(_))
+        | Instruction::OR(_, ref source, DataDirect(_)) => is_data(source),
+        Instruction::ADD(_, _, ref dest)
+        | Instruction::SUB(_, _, ref dest)
+        | Instruction::AND(_, _, ref dest)
+        | Instruction::OR(_, _, ref dest) => is_memory_alterable(dest),
+        Instruction::EOR(_, _, ref dest) => is_data_alterable(dest),
+
+        Instruction::ADDQ(ref size, _, ref dest) | Instruction::SUBQ(ref size, _, ref dest) => {
+            match *dest {
+                AddressDirect(_) => *size != DataSize::Byte,
+                _ => is_data_alterable(dest),
+            }
+        }
+
+        Instruction::MULU(_, ref source, _)
+        | Instruction::MULS(_, ref source, _)
+        | Instruction::DIVU(_, ref source, _)
+        | Instruction::DIVS(_, ref source, _)
+        | Instruction::CHK(_, ref source, _) => is_data(source),
+
+        Instruction::ASRD(_, _, ref dest)
+        | Instruction::ASLD(_, _, ref dest)
+        | Instruction::LSRD(_, _, ref dest)
+        | Instruction::LSLD(_, _, ref dest)
+        | Instruction::ROXRD(_, _, ref dest)
+        | Instruction::ROXLD(_, _, ref dest)
+        | Instruction::RORD(_, _, ref dest)
+        | Instruction::ROLD(_, _, ref dest) => match *dest {
+            DataDirect(_) => true,
+            _ => is_memory_alterable(dest),
+        },
+
+        Instruction::ST(_, _, ref ea)
+        | Instruction::TST(_, ref ea)
+        | Instruction::TAS(_, ref ea)
+        | Instruction::NBCD(ref ea)
+        | Instruction::NOT(_, ref ea)
+        | Instruction::NEG(_, ref ea)
+        | Instruction::NEGX(_, ref ea)
+        | Instruction::CLR(_, ref ea) => is_data_alterable(ea),
+
+        Instruction::LEA(ref ea, _)
+        | Instruction::PEA(ref ea)
+        | Instruction::JMP(ref ea)
+        | Instruction::JSR(ref ea) => is_control(ea),
+        Instruction::MOVEM(_, AddressIndirectPreDecrement(_), 0)
+        | Instruction::MOVEM(_, AddressIndirectPostIncrement(_), 1) => true,
+        Instruction::MOVEM(_, ref ea, 0) => is_control(ea) && is_memory_alterable(ea),
+        Instruction::MOVEM(_, ref ea, _) => is_control(ea),
+
+        _ => true,
+    }
+}
@@ -81,4 +81,8 @@ pub enum Instruction {
     NEG(DataSize, AddressingMode),
     NEGX(DataSize, AddressingMode),
     CLR(DataSize, AddressingMode),
+
+    Illegal,
+    LineA(u16),
+    LineF(u16),
 }
@@ -0,0 +1,42 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Collects the interrupt lines of the mapped hardware and presents the
+/// highest asserted level on the CPU's IPL inputs.
+#[derive(Default)]
+pub struct InterruptController {
+    lines: Vec<Rc<Cell<u8>>>,
+}
+
+impl InterruptController {
+    /// Creates a new line for a device to drive.
+    pub fn line(&mut self) -> InterruptLine {
+        let line = Rc::new(Cell::new(0));
+        self.lines.push(line.clone());
+        InterruptLine(line)
+    }
+
+    pub fn level(&self) -> u8 {
+        self.lines.iter().map(|line| line.get()).max().unwrap_or(0)
+    }
+}
+
+/// A level-sensitive interrupt request held by a device. The request stays
+/// asserted until the device deasserts it, usually once the interrupt
+/// handler has serviced it.
+#[derive(Clone, Debug)]
+pub struct InterruptLine(Rc<Cell<u8>>);
+
+impl InterruptLine {
+    pub fn assert(&self, level: u8) {
+        self.0.set(level & 0b111);
+    }
+
+    pub fn deassert(&self) {
+        self.0.set(0);
+    }
+
+    pub fn level(&self) -> u8 {
+        self.0.get()
+    }
+}
@@ -1,32 +1,62 @@
+/// A device's response to an interrupt acknowledge cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterruptAcknowledge {
+    /// The device puts its vector number on the data bus.
+    Vector(u8),
+    /// The device asserts VPA and the CPU uses the autovector for the level.
+    Autovector,
+    /// The cycle is terminated with BERR.
+    Spurious,
+}
+
 pub trait MappedHardware {
     fn tick(&mut self, cycles: usize) {}
 
+    /// Called when the reset line is asserted.
+    fn reset(&mut self) {}
+
+    /// Called with `true` before and `false` after an indivisible
+    /// read-modify-write cycle, such as the one run by TAS. Another bus
+    /// master must not be given the bus while it is locked.
+    fn set_locked(&mut self, _locked: bool) {}
+
+    /// Answers an interrupt acknowledge cycle for the given level, or `None`
+    /// when the device did not request an interrupt at that level.
+    fn interrupt_acknowledge(&mut self, _level: u8) -> Option<InterruptAcknowledge> {
+        None
+    }
+
+    /// Reads one byte lane: even addresses are the upper data strobe (bits
+    /// 15-8), odd addresses the lower (bits 7-0). The default reads the
+    /// whole word and picks the lane; devices whose registers have side
+    /// effects on read should answer byte cycles themselves.
     fn read_byte(&mut self, address: u32) -> Option<u8> {
-        if let Some(byte) = self.read_word(address) {
-            Some(byte as u8)
+        let word = self.read_word(address & !1)?;
+        if address & 1 == 0 {
+            Some((word >> 8) as u8)
         } else {
-            None
+            Some(word as u8)
         }
     }
 
+    /// Writes one byte lane, leaving the other half of the word alone. The
+    /// default does so by reading and rewriting the whole word; devices that
+    /// cannot tolerate that should answer byte cycles themselves.
     fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
-        let dest_word = self.read_word(address);
-        if let Some(dest_word) = dest_word {
-            let value = (value as u16) << 8;
-
-            let dest_word = value | (dest_word & 0xff);
-            self.write_word(address, dest_word);
-            Some(value)
+        let word = self.read_word(address & !1)?;
+        let word = if address & 1 == 0 {
+            (value as u16) << 8 | (word & 0x00ff)
         } else {
-            None
-        }
+            (word & 0xff00) | value as u16
+        };
+        self.write_word(address & !1, word)
     }
 
     fn read_word(&mut self, address: u32) -> Option<u16>;
 
     fn read_long(&mut self, address: u32) -> Option<u32> {
         let wh = self.read_word(address);
-        let wl = self.read_word(address + 2);
+        let wl = self.read_word(address.wrapping_add(2));
         match (wh, wl) {
             (Some(h), Some(l)) => Some(((h as u32) << 16) | l as u32),
             _ => None,
@@ -41,7 +71,7 @@ pub trait MappedHardware {
         if let None = writeh {
             return None;
         };
-        let writel = self.write_word(address + 2, wl);
+        let writel = self.write_word(address.wrapping_add(2), wl);
         if let None = writel {
             return None;
         };
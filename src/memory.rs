@@ -1,46 +1,129 @@
 use mapped_hardware::MappedHardware;
 
-pub struct Memory {
-    prg: Vec<u8>,
-    memory: Vec<u8>,
+const PAGE_SIZE: usize = 0x1000;
+
+/// Read/write memory of a fixed size. Pages are only allocated once they
+/// are first written, so a large RAM costs nothing until it is used and
+/// reads of untouched memory return zero.
+pub struct Ram {
+    size: usize,
+    pages: Vec<Option<Box<[u8; PAGE_SIZE]>>>,
 }
 
-impl Memory {
-    pub fn new(prg: Vec<u8>) -> Memory {
-        let memory = Memory {
-            prg: prg,
-            memory: vec![0; 0x1_0000_0000],
-        };
-        memory
+impl Ram {
+    pub fn new(size: usize) -> Ram {
+        Ram {
+            size,
+            pages: vec![None; size.div_ceil(PAGE_SIZE)],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Copies `data` into memory from `address`, as when loading a program.
+    /// Returns `None` if it does not fit.
+    pub fn load(&mut self, address: u32, data: &[u8]) -> Option<()> {
+        if address as usize + data.len() > self.size {
+            return None;
+        }
+        for (i, byte) in data.iter().enumerate() {
+            self.write_byte(address + i as u32, *byte);
+        }
+        Some(())
     }
 }
 
-impl MappedHardware for Memory {
-    fn read_word(&mut self, address: u32) -> Option<u16> {
-        if self.prg.len() > address as usize {
-            let hbyte = (self.prg[address as usize] as u16) << 8;
-            let lbyte = self.prg[(address + 1) as usize] as u16;
-            return Some(hbyte | lbyte);
-        } else {
-            let hbyte = (self.memory[address as usize] as u16) << 8;
-            let lbyte = self.memory[(address + 1) as usize] as u16;
-            return Some(hbyte | lbyte);
+impl MappedHardware for Ram {
+    fn read_byte(&mut self, address: u32) -> Option<u8> {
+        let address = address as usize;
+        if address >= self.size {
+            return None;
+        }
+        match self.pages[address / PAGE_SIZE] {
+            Some(ref page) => Some(page[address % PAGE_SIZE]),
+            None => Some(0),
         }
     }
 
+    fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+        let address = address as usize;
+        if address >= self.size {
+            return None;
+        }
+        let page = self.pages[address / PAGE_SIZE].get_or_insert_with(|| Box::new([0; PAGE_SIZE]));
+        page[address % PAGE_SIZE] = value;
+        Some(value as u16)
+    }
+
+    fn read_word(&mut self, address: u32) -> Option<u16> {
+        let hbyte = self.read_byte(address)?;
+        let lbyte = self.read_byte(address.wrapping_add(1))?;
+        Some((hbyte as u16) << 8 | lbyte as u16)
+    }
+
     fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
-        let hbyte = (value >> 8) as u8;
-        let lbyte = value as u8;
-        if self.prg.len() > (address + 1) as usize {
-            self.prg[address as usize] = hbyte;
-            self.prg[(address + 1) as usize] = lbyte;
-            // println!("prgwr");
-            Some(value)
-        } else {
-            self.memory[address as usize] = hbyte;
-            self.memory[(address + 1) as usize] = lbyte;
-            // println!("memwr a:{:?} v:{:?}", address, value);
-            Some(value)
+        if address as usize + 1 >= self.size {
+            return None;
+        }
+        self.write_byte(address, (value >> 8) as u8);
+        self.write_byte(address + 1, value as u8);
+        Some(value)
+    }
+}
+
+/// What a ROM does when the CPU writes to it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RomWrite {
+    /// The write is acknowledged and dropped.
+    Ignore,
+    /// The write is left unanswered so the CPU takes a bus error.
+    BusError,
+}
+
+/// Read-only memory holding a fixed image.
+pub struct Rom {
+    image: Vec<u8>,
+    writes: RomWrite,
+}
+
+impl Rom {
+    pub fn new(image: Vec<u8>, writes: RomWrite) -> Rom {
+        Rom { image, writes }
+    }
+
+    pub fn size(&self) -> usize {
+        self.image.len()
+    }
+
+    fn write(&self, address: u32, value: u16) -> Option<u16> {
+        if address as usize >= self.image.len() {
+            return None;
         }
+        match self.writes {
+            RomWrite::Ignore => Some(value),
+            RomWrite::BusError => None,
+        }
+    }
+}
+
+impl MappedHardware for Rom {
+    fn read_byte(&mut self, address: u32) -> Option<u8> {
+        self.image.get(address as usize).cloned()
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+        self.write(address, value as u16)
+    }
+
+    fn read_word(&mut self, address: u32) -> Option<u16> {
+        let hbyte = self.read_byte(address)?;
+        let lbyte = self.read_byte(address.wrapping_add(1))?;
+        Some((hbyte as u16) << 8 | lbyte as u16)
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+        self.write(address, value)
     }
 }
@@ -70,7 +70,7 @@ impl Registers {
     }
 
     pub fn pc_increment(&mut self) {
-        self.pc += 1
+        self.pc = self.pc.wrapping_add(1)
     }
 
     pub fn set_pc(&mut self, new_pc: u32) {
@@ -87,25 +87,35 @@ impl Registers {
     }
 
     fn get_current_stack_pointer(&self) -> StackPointer {
-        if self
-            .system_status_register
-            .contains(SupervisorStatusRegister::S)
-        {
+        if self.is_supervisor() {
             if self
                 .system_status_register
                 .contains(SupervisorStatusRegister::M)
             {
-                StackPointer::ISP
-            } else {
                 StackPointer::MSP
+            } else {
+                StackPointer::ISP
             }
         } else {
-            StackPointer::MSP
+            StackPointer::USP
+        }
+    }
+
+    pub fn is_supervisor(&self) -> bool {
+        self.system_status_register
+            .contains(SupervisorStatusRegister::S)
+    }
+
+    pub fn usp(&self) -> u32 {
+        match self.get_current_stack_pointer() {
+            StackPointer::USP => self.a[7],
+            _ => self.usp,
         }
     }
 
     pub fn set_usp(&mut self, new_value: u32) {
         self.usp = new_value;
+        self.apply_stack_pointer();
     }
 
     pub fn displace_sp(&mut self, value: Value) {
@@ -147,20 +157,22 @@ impl Registers {
     }
 
     pub fn set_ccr(&mut self, value: u8) {
-        self.save_current_stack_pointer();
-        self.ccr = ConditionCode::from_bits(value.into()).unwrap();
-        self.apply_stack_pointer();
+        self.ccr = ConditionCode::from_bits_truncate(value);
     }
 
     pub fn complete_ccr(&self) -> u16 {
         (self.system_status_register.bits() as u16) << 8 | self.ccr.bits() as u16
     }
 
+    /// Sets the whole status register, switching A7 between the user and
+    /// supervisor stack pointers when the S or M bit changes.
     pub fn set_complete_ccr(&mut self, value: u16) {
         let ssr = value >> 8;
         let ccr = value & 0xff;
+        self.save_current_stack_pointer();
         self.system_status_register = SupervisorStatusRegister::from_bits_truncate(ssr as u8);
-        self.ccr = ConditionCode::from_bits_truncate(value as u8);
+        self.ccr = ConditionCode::from_bits_truncate(ccr as u8);
+        self.apply_stack_pointer();
     }
 
     pub fn sr(&self) -> u16 {
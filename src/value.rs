@@ -49,7 +49,7 @@ impl Value {
                     cc.set(ConditionCode::N, true);
                 }
 
-                (Value::Byte(r as u8), cc)
+                (Value::Word(r), cc)
             }
             DataSize::LongWord => {
                 let s: u32 = self.into();
@@ -64,7 +64,7 @@ impl Value {
                     cc.set(ConditionCode::N, true);
                 }
 
-                (Value::Byte(r as u8), cc)
+                (Value::LongWord(r), cc)
             }
         }
     }
@@ -101,7 +101,7 @@ impl Value {
                     cc.set(ConditionCode::N, true);
                 }
 
-                (Value::Byte(r as u8), cc)
+                (Value::Word(r), cc)
             }
             DataSize::LongWord => {
                 let s: u32 = self.into();
@@ -116,7 +116,7 @@ impl Value {
                     cc.set(ConditionCode::N, true);
                 }
 
-                (Value::Byte(r as u8), cc)
+                (Value::LongWord(r), cc)
             }
         }
     }
@@ -260,6 +260,183 @@ impl Value {
             }
         }
     }
+
+    /// Adds `value` and the X flag of `ccr`, the way ADDX does. Z is only
+    /// ever cleared, so a multi-precision result tests zero as a whole.
+    pub fn addx_cc(
+        self,
+        size: DataSize,
+        value: Value,
+        ccr: ConditionCode,
+    ) -> (Value, ConditionCode) {
+        let x = ccr.contains(ConditionCode::X);
+        let (result, cc) = add_with_carry(size, self.into(), value.into(), x, false);
+        (result, sticky_zero(cc, ccr))
+    }
+
+    /// Subtracts `value` and the X flag of `ccr`, with the same Z rule as
+    /// `addx_cc`.
+    pub fn subx_cc(
+        self,
+        size: DataSize,
+        value: Value,
+        ccr: ConditionCode,
+    ) -> (Value, ConditionCode) {
+        let x = ccr.contains(ConditionCode::X);
+        let (result, cc) = add_with_carry(size, self.into(), value.into(), x, true);
+        (result, sticky_zero(cc, ccr))
+    }
+
+    /// Condition codes for comparing this value with `value`. A comparison
+    /// leaves X alone, so it is always clear in the result.
+    pub fn cmp_cc(self, size: DataSize, value: Value) -> ConditionCode {
+        let (_, mut cc) = add_with_carry(size, self.into(), value.into(), false, true);
+        cc.remove(ConditionCode::X);
+        cc
+    }
+
+    pub fn neg_cc(self, size: DataSize) -> (Value, ConditionCode) {
+        add_with_carry(size, 0, self.into(), false, true)
+    }
+
+    pub fn negx_cc(self, size: DataSize, ccr: ConditionCode) -> (Value, ConditionCode) {
+        Value::from_raw(size, 0).subx_cc(size, self, ccr)
+    }
+
+    /// Adds two packed BCD bytes plus the X flag of `ccr`. Z is only ever
+    /// cleared, so a multi-byte result tests zero as a whole. N and V follow
+    /// the undocumented behaviour of the 68000.
+    pub fn abcd_cc(self, value: Value, ccr: ConditionCode) -> (Value, ConditionCode) {
+        let s: u8 = self.into();
+        let v: u8 = value.into();
+        let x = ccr.contains(ConditionCode::X) as u32;
+
+        let mut r = (s & 0x0f) as u32 + (v & 0x0f) as u32 + x;
+        let mut overflow = !r;
+        if r > 9 {
+            r += 6;
+        }
+        r += (s & 0xf0) as u32 + (v & 0xf0) as u32;
+
+        let carry = r > 0x99;
+        if carry {
+            r -= 0xa0;
+        }
+        overflow &= r;
+
+        bcd_result(r as u8, carry, overflow & 0x80 != 0, ccr)
+    }
+
+    /// Subtracts `value` and the X flag of `ccr` from this packed BCD byte,
+    /// with the same sticky Z and undocumented N/V results as `abcd_cc`.
+    pub fn sbcd_cc(self, value: Value, ccr: ConditionCode) -> (Value, ConditionCode) {
+        let s: u8 = self.into();
+        let v: u8 = value.into();
+        let x = ccr.contains(ConditionCode::X) as u32;
+
+        let mut r = ((s & 0x0f) as u32).wrapping_sub((v & 0x0f) as u32 + x);
+        let mut overflow = !r;
+        if r > 9 {
+            r = r.wrapping_sub(6);
+        }
+        r = r
+            .wrapping_add((s & 0xf0) as u32)
+            .wrapping_sub((v & 0xf0) as u32);
+
+        let carry = r > 0x99;
+        if carry {
+            r = r.wrapping_add(0xa0);
+        }
+        let r = r & 0xff;
+        overflow &= r;
+
+        bcd_result(r as u8, carry, overflow & 0x80 != 0, ccr)
+    }
+
+    /// Negates this packed BCD byte, subtracting it and the X flag of `ccr`
+    /// from zero.
+    pub fn nbcd_cc(self, ccr: ConditionCode) -> (Value, ConditionCode) {
+        let s: u8 = self.into();
+        let x = ccr.contains(ConditionCode::X) as u8;
+
+        let r = 0x9au8.wrapping_sub(s).wrapping_sub(x);
+        if r == 0x9a {
+            return bcd_result(0, false, false, ccr);
+        }
+
+        let mut overflow = !r;
+        let r = if r & 0x0f == 0x0a {
+            (r & 0xf0).wrapping_add(0x10)
+        } else {
+            r
+        };
+        overflow &= r;
+
+        bcd_result(r, true, overflow & 0x80 != 0, ccr)
+    }
+}
+
+/// Adds or subtracts `source` and a carry in, setting all five flags the
+/// way the 68000's ALU does.
+fn add_with_carry(
+    size: DataSize,
+    destination: u32,
+    source: u32,
+    carry: bool,
+    subtract: bool,
+) -> (Value, ConditionCode) {
+    let mask: u64 = match size {
+        DataSize::Byte => 0xff,
+        DataSize::Word => 0xffff,
+        DataSize::LongWord => 0xffff_ffff,
+    };
+    let sign = (mask + 1) >> 1;
+    let d = destination as u64 & mask;
+    let s = source as u64 & mask;
+    let c = carry as u64;
+
+    let (r, carry, overflow) = if subtract {
+        let r = d.wrapping_sub(s).wrapping_sub(c) & mask;
+        (r, s + c > d, (d ^ s) & (d ^ r) & sign != 0)
+    } else {
+        let r = d + s + c;
+        (r & mask, r > mask, !(d ^ s) & (d ^ r) & sign != 0)
+    };
+
+    let mut cc = ConditionCode::empty();
+    cc.set(ConditionCode::X, carry);
+    cc.set(ConditionCode::C, carry);
+    cc.set(ConditionCode::V, overflow);
+    cc.set(ConditionCode::N, r & sign != 0);
+    cc.set(ConditionCode::Z, r == 0);
+
+    (Value::from_raw(size, r as u32), cc)
+}
+
+fn sticky_zero(mut cc: ConditionCode, ccr: ConditionCode) -> ConditionCode {
+    if !ccr.contains(ConditionCode::Z) {
+        cc.remove(ConditionCode::Z);
+    }
+    cc
+}
+
+fn bcd_result(
+    result: u8,
+    carry: bool,
+    overflow: bool,
+    ccr: ConditionCode,
+) -> (Value, ConditionCode) {
+    let mut cc = ConditionCode::empty();
+    cc.set(ConditionCode::X, carry);
+    cc.set(ConditionCode::C, carry);
+    cc.set(ConditionCode::V, overflow);
+    cc.set(ConditionCode::N, result & 0x80 == 0x80);
+    cc.set(
+        ConditionCode::Z,
+        result == 0 && ccr.contains(ConditionCode::Z),
+    );
+
+    (Value::Byte(result), cc)
 }
 
 impl Into<i32> for Value {
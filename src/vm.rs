@@ -1,11 +1,12 @@
-use bus::Bus;
-use cpu::Cpu;
+use bus::{Bus, MapError, UnmappedAccess};
+use cpu::{AddressBus, Cpu, CpuState, Exception};
+use interrupt::{InterruptController, InterruptLine};
 use mapped_hardware::MappedHardware;
-use memory::Memory;
 
 pub struct VirtualMachine {
     cpu: Cpu,
     bus: Bus,
+    interrupts: InterruptController,
 }
 
 impl VirtualMachine {
@@ -16,24 +17,53 @@ impl VirtualMachine {
         VirtualMachine {
             cpu: cpu,
             bus: Bus::default(),
+            interrupts: InterruptController::default(),
         }
     }
 
-    pub fn map_hardware(&mut self, hardware: Box<MappedHardware>) {
-        self.bus.map_hardware(hardware);
+    /// Maps a device into the address space, see `Bus::map_hardware`.
+    pub fn map_hardware(
+        &mut self,
+        base: u32,
+        size: u32,
+        mirror_mask: Option<u32>,
+        hardware: Box<MappedHardware>,
+    ) -> Result<(), MapError> {
+        self.bus.map_hardware(base, size, mirror_mask, hardware)
     }
 
+    /// Hands out an interrupt line for a device to assert from its `tick`.
+    pub fn interrupt_line(&mut self) -> InterruptLine {
+        self.interrupts.line()
+    }
+
+    pub fn set_address_bus(&mut self, address_bus: AddressBus) {
+        self.cpu.set_address_bus(address_bus);
+    }
+
+    pub fn set_unmapped_access(&mut self, unmapped_access: UnmappedAccess) {
+        self.bus.set_unmapped_access(unmapped_access);
+    }
+
+    /// Resets the peripherals and the processor, as on power-up.
     pub fn init(&mut self) {
+        self.bus.reset();
         self.cpu.reset(&mut self.bus);
     }
 
-    pub fn tick(&mut self) {
-        self.cpu.execute_next_instruction(&mut self.bus);
+    pub fn state(&self) -> CpuState {
+        self.cpu.state()
+    }
+
+    pub fn tick(&mut self) -> Option<Exception> {
+        self.cpu.set_interrupt_level(self.interrupts.level());
+        let exception = self.cpu.execute_next_instruction(&mut self.bus);
         // println!("Cycles: {}", self.bus.cycles);
         // let bus = &self.bus;
         // if let Some(byte) = bus.read_byte(0) {
         //     println!("{:X}", byte);
         // }
+        exception
     }
     pub fn run(&mut self) {
         loop {
@@ -0,0 +1,188 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_address {
+    use common::{boot, PROGRAM, STACK};
+    use m68k::mapped_hardware::MappedHardware;
+
+    const X: u16 = 0b1_0000;
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+    const V: u16 = 0b0_0010;
+    const C: u16 = 0b0_0001;
+
+    fn ccr(cpu: &m68k::cpu::Cpu) -> u16 {
+        cpu.registers.sr() & 0x1f
+    }
+
+    #[test]
+    fn test_adda_sign_extends_words() {
+        // adda.w d0,a0
+        // adda.l d0,a0
+        let (mut cpu, mut ram) = boot(&[0xD0C0, 0xD1C0]);
+        cpu.registers.set_data(0, 0x0000_ffff);
+        cpu.registers.set_address(0, 0x1000);
+        cpu.registers.set_ccr((X | Z | C) as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.address(0), 0x0fff);
+        assert_eq!(ccr(&cpu), X | Z | C);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.address(0), 0x0001_0ffe);
+        assert_eq!(ccr(&cpu), X | Z | C);
+    }
+
+    #[test]
+    fn test_suba() {
+        // suba.w d0,a0
+        let (mut cpu, mut ram) = boot(&[0x90C0]);
+        cpu.registers.set_data(0, 0x8000);
+        cpu.registers.set_address(0, 0);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.address(0), 0x8000);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_quick_address_arithmetic() {
+        // addq.w #1,a0
+        // subq.w #2,a0
+        let (mut cpu, mut ram) = boot(&[0x5248, 0x5548]);
+        cpu.registers.set_address(0, 0xffff);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.address(0), 0x0001_0000);
+        assert_eq!(ccr(&cpu), 0);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.address(0), 0xfffe);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_cmpa_sign_extends_words() {
+        // cmpa.w d0,a0
+        let (mut cpu, mut ram) = boot(&[0xB0C0]);
+        cpu.registers.set_data(0, 0xffff);
+        cpu.registers.set_address(0, 0xffff_ffff);
+        cpu.registers.set_ccr(X as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ccr(&cpu), X | Z);
+        assert_eq!(cpu.registers.address(0), 0xffff_ffff);
+    }
+
+    #[test]
+    fn test_cmpm_post_increments_both() {
+        // cmpm.b (a0)+,(a1)+
+        let (mut cpu, mut ram) = boot(&[0xB308]);
+        ram.write_word(0x3000, 0x0500);
+        ram.write_word(0x3100, 0x0300);
+        cpu.registers.set_address(0, 0x3000);
+        cpu.registers.set_address(1, 0x3100);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ccr(&cpu), N | C);
+        assert_eq!(cpu.registers.address(0), 0x3001);
+        assert_eq!(cpu.registers.address(1), 0x3101);
+    }
+
+    #[test]
+    fn test_cmp_overflow() {
+        // cmp.b d0,d1
+        let (mut cpu, mut ram) = boot(&[0xB200, 0xB200]);
+        cpu.registers.set_data(0, 0x01);
+        cpu.registers.set_data(1, 0x180);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ccr(&cpu), V);
+
+        cpu.registers.set_data(0, 0x00);
+        cpu.registers.set_data(1, 0x100);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ccr(&cpu), Z);
+    }
+
+    #[test]
+    fn test_indexed_negative_word_index() {
+        // move.w 0(a0,d0.w),d1
+        let (mut cpu, mut ram) = boot(&[0x3230, 0x0000]);
+        ram.write_word(0x2ffe, 0x1234);
+        cpu.registers.set_address(0, 0x3000);
+        cpu.registers.set_data(0, 0x0001_fffe);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0x1234);
+    }
+
+    #[test]
+    fn test_indexed_long_index_and_negative_displacement() {
+        // move.w -4(a0,a1.l),d1
+        let (mut cpu, mut ram) = boot(&[0x3230, 0x98FC]);
+        ram.write_word(0x3ffc, 0x5678);
+        cpu.registers.set_address(0, 0x3000);
+        cpu.registers.set_address(1, 0x1000);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0x5678);
+    }
+
+    #[test]
+    fn test_indexed_ignores_scale_and_full_format_bits() {
+        // move.w 2(a0,d0.w*8),d1 with bit 8 set, read as 2(a0,d0.w)
+        let (mut cpu, mut ram) = boot(&[0x3230, 0x0702]);
+        ram.write_word(0x3006, 0x9abc);
+        cpu.registers.set_address(0, 0x3000);
+        cpu.registers.set_data(0, 4);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0x9abc);
+    }
+
+    #[test]
+    fn test_pc_indexed() {
+        // move.w 2(pc,d0.w),d1
+        let (mut cpu, mut ram) = boot(&[0x323B, 0x0002, 0x4E71, 0x4E71, 0xBEEF]);
+        cpu.registers.set_data(0, 4);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0xbeef);
+    }
+
+    #[test]
+    fn test_pc_displacement() {
+        // move.w 6(pc),d1
+        // lea -4(pc,a0.l),a1
+        let (mut cpu, mut ram) = boot(&[0x323A, 0x0006, 0x43FB, 0x88FC, 0xBEEF]);
+        cpu.registers.set_address(0, 0x100);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0xbeef);
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.address(1), PROGRAM + 6 - 4 + 0x100);
+    }
+
+    #[test]
+    fn test_quick_eight() {
+        // addq.l #8,a0
+        // subq.l #8,sp
+        // addq.w #8,d0
+        let (mut cpu, mut ram) = boot(&[0x5088, 0x518F, 0x5040]);
+        cpu.registers.set_address(0, 0x100);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.address(0), 0x108);
+        assert_eq!(cpu.registers.sp(), STACK - 8);
+        assert_eq!(cpu.registers.data(0), 8);
+    }
+}
@@ -0,0 +1,132 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_address_bus {
+    use common::{Fixture, Ram, PROGRAM, STACK};
+    use m68k::bus::Bus;
+    use m68k::cpu::{AddressBus, Cpu, Exception};
+    use m68k::mapped_hardware::MappedHardware;
+
+    #[test]
+    fn test_top_byte_is_ignored() {
+        // move.w (a0),d0
+        // move.l d0,(a1)
+        let mut ram = Ram::with_program(&[0x3010, 0x2280]);
+        ram.write_word(0x3000, 0x1234);
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut ram);
+        cpu.registers.set_address(0, 0x8000_3000);
+        cpu.registers.set_address(1, 0xff00_3100);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(0) & 0xffff, 0x1234);
+        assert_eq!(ram.read_word(0x3102), Some(0x1234));
+    }
+
+    #[test]
+    fn test_fetch_and_vectors_are_masked() {
+        // the reset vector points into a mirror of the program
+        let mut ram = Ram::with_program(&[0x4E71, 0x4E40]);
+        ram.write_long(4, 0xa500_0000 | PROGRAM);
+        ram.set_vector(32, 0xff00_0000 | (PROGRAM + 0x100));
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut ram);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::Trap(0))
+        );
+        assert_eq!(cpu.registers.pc(), 0xff00_0000 | (PROGRAM + 0x100));
+        assert_eq!(ram.read_long(STACK - 4), Some(0xa500_0000 | (PROGRAM + 4)));
+    }
+
+    #[test]
+    fn test_long_access_wraps_around_the_bus() {
+        // move.l (a0),d0
+        let ram = Ram::with_program(&[0x2010]);
+        let mut bus = Bus::default();
+        let mut top = Ram::new(0x100);
+        top.write_word(0xfe, 0xabcd);
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0xff_ff00, 0x100, None, Box::new(top))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0xff_fffe);
+
+        assert_eq!(cpu.execute_next_instruction(&mut bus), None);
+        assert_eq!(cpu.registers.data(0), 0xabcd_0000);
+    }
+
+    #[test]
+    fn test_68008_bus() {
+        // move.w (a0),d0
+        let mut ram = Ram::with_program(&[0x3010]);
+        ram.write_word(0x3000, 0x5678);
+        let mut cpu = Cpu::default();
+        cpu.set_address_bus(AddressBus::Bits20);
+        cpu.reset(&mut ram);
+        cpu.registers.set_address(0, 0x0030_3000);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.data(0) & 0xffff, 0x5678);
+    }
+
+    #[test]
+    fn test_32_bit_bus() {
+        // move.w (a0),d0
+        let mut ram = Ram::with_program(&[0x3010]);
+        let mut cpu = Cpu::default();
+        cpu.set_address_bus(AddressBus::Bits32);
+        cpu.reset(&mut ram);
+        cpu.registers.set_address(0, 0x0100_3000);
+
+        match cpu.execute_next_instruction(&mut ram) {
+            Some(Exception::BusError(fault)) => assert_eq!(fault.address, 0x0100_3000),
+            exception => panic!("expected a bus error, got {:?}", exception),
+        }
+    }
+
+    #[test]
+    fn test_post_increment_wraps_around() {
+        // move.l (a0)+,d0
+        let ram = Ram::with_program(&[0x2018]);
+        let mut top = Ram::new(0x1_0000);
+        top.write_long(0xfffc, 0x1234_5678);
+        let mut bus = Bus::default();
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0xff_0000, 0x1_0000, None, Box::new(top))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0xffff_fffc);
+
+        assert_eq!(cpu.execute_next_instruction(&mut bus), None);
+        assert_eq!(cpu.registers.data(0), 0x1234_5678);
+        assert_eq!(cpu.registers.address(0), 0);
+    }
+
+    #[test]
+    fn test_absolute_short_is_sign_extended() {
+        // move.w $8000.w,d0
+        // move.w d0,$8002.w
+        let ram = Ram::with_program(&[0x3038, 0x8000, 0x31C0, 0x8002]);
+        let mut top = Ram::new(0x1_0000);
+        top.write_word(0x8000, 0x1234);
+        let mut bus = Bus::default();
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0xff_0000, 0x1_0000, None, Box::new(top))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+
+        assert_eq!(cpu.execute_next_instruction(&mut bus), None);
+        assert_eq!(cpu.execute_next_instruction(&mut bus), None);
+        assert_eq!(cpu.registers.data(0) & 0xffff, 0x1234);
+        assert_eq!(bus.read_word(0xff_8002), Some(0x1234));
+    }
+}
@@ -0,0 +1,140 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_bcd {
+    use common::boot;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const X: u16 = 0b1_0000;
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+    const C: u16 = 0b0_0001;
+
+    #[test]
+    fn test_abcd_register() {
+        // abcd d0,d1
+        let (mut cpu, mut ram) = boot(&[0xC300]);
+        cpu.registers.set_data(0, 0x45);
+        cpu.registers.set_data(1, 0xffff_ff38);
+        cpu.registers.set_ccr(Z as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0xffff_ff83);
+        assert_eq!(cpu.registers.sr() & C, 0);
+        assert_eq!(cpu.registers.sr() & X, 0);
+        assert_eq!(cpu.registers.sr() & Z, 0);
+    }
+
+    #[test]
+    fn test_abcd_carry_and_sticky_zero() {
+        // abcd d0,d1
+        let (mut cpu, mut ram) = boot(&[0xC300]);
+        cpu.registers.set_data(0, 0x00);
+        cpu.registers.set_data(1, 0x99);
+        cpu.registers.set_ccr((X | Z) as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x00);
+        assert_eq!(cpu.registers.sr() & C, C);
+        assert_eq!(cpu.registers.sr() & X, X);
+        assert_eq!(cpu.registers.sr() & Z, Z);
+    }
+
+    #[test]
+    fn test_abcd_memory_chain() {
+        // abcd -(a0),-(a1) twice: 1999 + 0001 = 2000
+        let (mut cpu, mut ram) = boot(&[0xC308, 0xC308]);
+        ram.write_word(0x3000, 0x1999);
+        ram.write_word(0x3100, 0x0001);
+        cpu.registers.set_address(0, 0x3002);
+        cpu.registers.set_address(1, 0x3102);
+        cpu.registers.set_ccr(Z as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3100), Some(0x2000));
+        assert_eq!(ram.read_word(0x3000), Some(0x1999));
+        assert_eq!(cpu.registers.address(0), 0x3000);
+        assert_eq!(cpu.registers.address(1), 0x3100);
+        assert_eq!(cpu.registers.sr() & C, 0);
+        assert_eq!(cpu.registers.sr() & Z, 0);
+    }
+
+    #[test]
+    fn test_sbcd_register() {
+        // sbcd d0,d1
+        let (mut cpu, mut ram) = boot(&[0x8300]);
+        cpu.registers.set_data(0, 0x15);
+        cpu.registers.set_data(1, 0x42);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x27);
+        assert_eq!(cpu.registers.sr() & C, 0);
+    }
+
+    #[test]
+    fn test_sbcd_borrow() {
+        // sbcd d0,d1
+        let (mut cpu, mut ram) = boot(&[0x8300]);
+        cpu.registers.set_data(0, 0x00);
+        cpu.registers.set_data(1, 0x00);
+        cpu.registers.set_ccr(X as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x99);
+        assert_eq!(cpu.registers.sr() & C, C);
+        assert_eq!(cpu.registers.sr() & X, X);
+        assert_eq!(cpu.registers.sr() & N, N);
+    }
+
+    #[test]
+    fn test_sbcd_memory_chain() {
+        // sbcd -(a0),-(a1) twice: 2000 - 0001 = 1999
+        let (mut cpu, mut ram) = boot(&[0x8308, 0x8308]);
+        ram.write_word(0x3000, 0x0001);
+        ram.write_word(0x3100, 0x2000);
+        cpu.registers.set_address(0, 0x3002);
+        cpu.registers.set_address(1, 0x3102);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3100), Some(0x1999));
+        assert_eq!(cpu.registers.address(1), 0x3100);
+        assert_eq!(cpu.registers.sr() & C, 0);
+    }
+
+    #[test]
+    fn test_nbcd() {
+        // nbcd d0
+        let (mut cpu, mut ram) = boot(&[0x4800]);
+        cpu.registers.set_data(0, 0x25);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x75);
+        assert_eq!(cpu.registers.sr() & C, C);
+        assert_eq!(cpu.registers.sr() & X, X);
+    }
+
+    #[test]
+    fn test_nbcd_zero() {
+        // nbcd d0
+        let (mut cpu, mut ram) = boot(&[0x4800]);
+        cpu.registers.set_data(0, 0x00);
+        cpu.registers.set_ccr(Z as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x00);
+        assert_eq!(cpu.registers.sr() & C, 0);
+        assert_eq!(cpu.registers.sr() & Z, Z);
+    }
+}
@@ -0,0 +1,84 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_bit {
+    use common::boot;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const Z: u16 = 0b0_0100;
+
+    #[test]
+    fn test_btst_register_modulo_32() {
+        // btst d1,d0
+        let (mut cpu, mut ram) = boot(&[0x0300, 0x0300]);
+        cpu.registers.set_data(0, 0b10);
+        cpu.registers.set_data(1, 33);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.sr() & Z, 0);
+
+        cpu.registers.set_data(1, 32);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.sr() & Z, Z);
+        assert_eq!(cpu.registers.data(0), 0b10);
+    }
+
+    #[test]
+    fn test_bset_static_memory_modulo_8() {
+        // bset #11,(a0)
+        let (mut cpu, mut ram) = boot(&[0x08D0, 11]);
+        ram.write_word(0x3000, 0x0000);
+        cpu.registers.set_address(0, 0x3000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3000), Some(0x0800));
+        assert_eq!(cpu.registers.sr() & Z, Z);
+        assert_eq!(cpu.registers.pc(), 0x2004);
+    }
+
+    #[test]
+    fn test_bset_static_absolute() {
+        // bset #1,$3001.w
+        let (mut cpu, mut ram) = boot(&[0x08F8, 1, 0x3001]);
+        ram.write_word(0x3000, 0x0002);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3000), Some(0x0002));
+        assert_eq!(cpu.registers.sr() & Z, 0);
+        assert_eq!(cpu.registers.pc(), 0x2006);
+    }
+
+    #[test]
+    fn test_bclr_dynamic_post_increment() {
+        // bclr d1,(a0)+
+        let (mut cpu, mut ram) = boot(&[0x0398]);
+        ram.write_word(0x3000, 0xff00);
+        cpu.registers.set_address(0, 0x3000);
+        cpu.registers.set_data(1, 7);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3000), Some(0x7f00));
+        assert_eq!(cpu.registers.address(0), 0x3001);
+        assert_eq!(cpu.registers.sr() & Z, 0);
+    }
+
+    #[test]
+    fn test_bchg_register() {
+        // bchg #31,d0
+        let (mut cpu, mut ram) = boot(&[0x0840, 31, 0x0840, 31]);
+        cpu.registers.set_data(0, 0x1234);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x8000_1234);
+        assert_eq!(cpu.registers.sr() & Z, Z);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x1234);
+        assert_eq!(cpu.registers.sr() & Z, 0);
+    }
+}
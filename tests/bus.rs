@@ -0,0 +1,111 @@
+extern crate m68k;
+
+#[cfg(test)]
+mod test_bus {
+    use m68k::bus::{Bus, MapError};
+    use m68k::mapped_hardware::MappedHardware;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Writes = Rc<RefCell<Vec<(u32, u16)>>>;
+
+    /// Answers every read with the offset it was handed and records writes.
+    struct Probe {
+        writes: Writes,
+    }
+
+    impl MappedHardware for Probe {
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            Some(address as u16)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            self.writes.borrow_mut().push((address, value));
+            Some(value)
+        }
+    }
+
+    fn probe() -> (Box<Probe>, Writes) {
+        let writes = Rc::new(RefCell::new(Vec::new()));
+        (
+            Box::new(Probe {
+                writes: writes.clone(),
+            }),
+            writes,
+        )
+    }
+
+    #[test]
+    fn test_offsets_are_relative_to_base() {
+        let mut bus = Bus::default();
+        let (device, writes) = probe();
+        bus.map_hardware(0xff_0000, 0x1000, None, device).unwrap();
+
+        assert_eq!(bus.read_word(0xff_0000), Some(0x0000));
+        assert_eq!(bus.read_word(0xff_0ffe), Some(0x0ffe));
+        assert_eq!(bus.write_word(0xff_0100, 0x1234), Some(0x1234));
+        assert_eq!(*writes.borrow(), vec![(0x0100, 0x1234)]);
+    }
+
+    #[test]
+    fn test_unmapped_access_is_unanswered() {
+        let mut bus = Bus::default();
+        let (device, _) = probe();
+        bus.map_hardware(0x1000, 0x100, None, device).unwrap();
+
+        assert_eq!(bus.read_word(0x0ffe), None);
+        assert_eq!(bus.read_word(0x1100), None);
+        assert_eq!(bus.write_word(0x8000_0000, 0), None);
+    }
+
+    #[test]
+    fn test_mirror_mask() {
+        let mut bus = Bus::default();
+        let (device, _) = probe();
+        bus.map_hardware(0xa0_0000, 0x1_0000, Some(0x1fff), device)
+            .unwrap();
+
+        assert_eq!(bus.read_word(0xa0_0010), Some(0x0010));
+        assert_eq!(bus.read_word(0xa0_2010), Some(0x0010));
+        assert_eq!(bus.read_word(0xa0_fffe), Some(0x1ffe));
+    }
+
+    #[test]
+    fn test_overlap_is_rejected() {
+        let mut bus = Bus::default();
+        let (first, _) = probe();
+        let (second, _) = probe();
+        let (third, _) = probe();
+        bus.map_hardware(0x1000, 0x1000, None, first).unwrap();
+
+        assert_eq!(
+            bus.map_hardware(0x1f00, 0x200, None, second),
+            Err(MapError::Overlap {
+                base: 0x1000,
+                size: 0x1000,
+            })
+        );
+        assert_eq!(bus.map_hardware(0x2000, 0x100, None, third), Ok(()));
+        assert_eq!(bus.read_word(0x1f00), Some(0x0f00));
+        assert_eq!(bus.read_word(0x2000), Some(0x0000));
+    }
+
+    #[test]
+    fn test_bad_ranges_are_rejected() {
+        let mut bus = Bus::default();
+        let (unaligned, _) = probe();
+        let (out_of_range, _) = probe();
+        let (top, _) = probe();
+
+        assert_eq!(
+            bus.map_hardware(0x1080, 0x100, None, unaligned),
+            Err(MapError::Unaligned)
+        );
+        assert_eq!(
+            bus.map_hardware(0xffff_ff00, 0x200, None, out_of_range),
+            Err(MapError::OutOfRange)
+        );
+        assert_eq!(bus.map_hardware(0xffff_ff00, 0x100, None, top), Ok(()));
+        assert_eq!(bus.read_word(0xffff_fffe), Some(0x00fe));
+    }
+}
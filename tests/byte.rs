@@ -0,0 +1,148 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_byte {
+    use common::{Fixture, Ram, PROGRAM};
+    use m68k::bus::Bus;
+    use m68k::cpu::Cpu;
+    use m68k::mapped_hardware::MappedHardware;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A register file that only decodes word cycles.
+    struct Latch {
+        words: [u16; 4],
+    }
+
+    impl MappedHardware for Latch {
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            self.words.get(address as usize / 2).cloned()
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            self.words[address as usize / 2] = value;
+            Some(value)
+        }
+    }
+
+    /// A receive FIFO on the low lane that pops on every read, next to a
+    /// control register on the high lane.
+    struct Uart {
+        received: Vec<u8>,
+        control: u8,
+        word_cycles: Rc<RefCell<usize>>,
+    }
+
+    impl MappedHardware for Uart {
+        fn read_byte(&mut self, address: u32) -> Option<u8> {
+            match address {
+                0 => Some(self.control),
+                1 => Some(if self.received.is_empty() {
+                    0
+                } else {
+                    self.received.remove(0)
+                }),
+                _ => None,
+            }
+        }
+
+        fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+            match address {
+                0 => self.control = value,
+                1 => (),
+                _ => return None,
+            }
+            Some(value as u16)
+        }
+
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            *self.word_cycles.borrow_mut() += 1;
+            let high = self.read_byte(address)?;
+            let low = self.read_byte(address + 1)?;
+            Some((high as u16) << 8 | low as u16)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            *self.word_cycles.borrow_mut() += 1;
+            self.write_byte(address, (value >> 8) as u8)?;
+            self.write_byte(address + 1, value as u8)?;
+            Some(value)
+        }
+    }
+
+    #[test]
+    fn test_default_byte_lanes() {
+        let mut latch = Latch {
+            words: [0x1234, 0, 0, 0],
+        };
+
+        assert_eq!(latch.read_byte(0), Some(0x12));
+        assert_eq!(latch.read_byte(1), Some(0x34));
+        latch.write_byte(2, 0xab);
+        latch.write_byte(5, 0xcd);
+        assert_eq!(latch.words, [0x1234, 0xab00, 0x00cd, 0]);
+    }
+
+    #[test]
+    fn test_cpu_byte_access_to_word_device() {
+        // move.b (a0)+,d0
+        // move.b d0,(a1)
+        let mut bus = Bus::default();
+        let latch = Latch {
+            words: [0x1234, 0x5678, 0, 0],
+        };
+        bus.map_hardware(
+            0,
+            0x1_0000,
+            None,
+            Box::new(Ram::with_program(&[0x1018, 0x1280])),
+        )
+        .unwrap();
+        bus.map_hardware(0x1_0000, 0x100, None, Box::new(latch))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0x1_0003);
+        cpu.registers.set_address(1, 0x1_0002);
+
+        cpu.execute_next_instruction(&mut bus);
+        cpu.execute_next_instruction(&mut bus);
+
+        assert_eq!(cpu.registers.data(0) & 0xff, 0x78);
+        assert_eq!(bus.read_word(0x1_0002), Some(0x7878));
+        assert_eq!(cpu.registers.pc(), PROGRAM + 4);
+    }
+
+    #[test]
+    fn test_cpu_byte_access_is_native() {
+        // move.b (a0),d0
+        // move.b (a0),d1
+        // move.b d2,-(a0)
+        let word_cycles = Rc::new(RefCell::new(0));
+        let uart = Uart {
+            received: vec![b'h', b'i'],
+            control: 0,
+            word_cycles: word_cycles.clone(),
+        };
+        let mut bus = Bus::default();
+        let ram = Ram::with_program(&[0x1010, 0x1210, 0x1102]);
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0xff_0000, 0x100, None, Box::new(uart))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0xff_0001);
+        cpu.registers.set_data(2, 0x03);
+
+        cpu.execute_next_instruction(&mut bus);
+        cpu.execute_next_instruction(&mut bus);
+        cpu.execute_next_instruction(&mut bus);
+
+        assert_eq!(cpu.registers.data(0) & 0xff, b'h' as u32);
+        assert_eq!(cpu.registers.data(1) & 0xff, b'i' as u32);
+        assert_eq!(bus.read_byte(0xff_0000), Some(0x03));
+        assert_eq!(*word_cycles.borrow(), 0);
+    }
+}
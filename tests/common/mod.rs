@@ -0,0 +1,81 @@
+#![allow(dead_code)]
+
+use m68k::cpu::Cpu;
+use m68k::mapped_hardware::MappedHardware;
+pub use m68k::memory::Ram;
+
+pub const STACK: u32 = 0x1000;
+pub const PROGRAM: u32 = 0x2000;
+
+/// Helpers for setting up a test RAM.
+pub trait Fixture {
+    /// Builds a 64 KiB RAM with a reset vector pointing at `PROGRAM` and
+    /// the program copied there.
+    fn with_program(program: &[u16]) -> Self;
+
+    fn load_words(&mut self, address: u32, words: &[u16]);
+
+    fn set_vector(&mut self, vector: u32, handler: u32);
+}
+
+impl Fixture for Ram {
+    fn with_program(program: &[u16]) -> Ram {
+        let mut ram = Ram::new(0x1_0000);
+        ram.write_long(0, STACK);
+        ram.write_long(4, PROGRAM);
+        ram.load_words(PROGRAM, program);
+        ram
+    }
+
+    fn load_words(&mut self, address: u32, words: &[u16]) {
+        for (i, word) in words.iter().enumerate() {
+            self.write_word(address + i as u32 * 2, *word);
+        }
+    }
+
+    fn set_vector(&mut self, vector: u32, handler: u32) {
+        self.write_long(vector * 4, handler);
+    }
+}
+
+/// Resets a CPU running `program` from `PROGRAM`.
+pub fn boot(program: &[u16]) -> (Cpu, Ram) {
+    let mut ram = Ram::with_program(program);
+    let mut cpu = Cpu::default();
+    cpu.reset(&mut ram);
+    (cpu, ram)
+}
+
+/// Wraps a RAM and counts the internal CPU cycles reported through `tick`.
+pub struct Clocked {
+    pub ram: Ram,
+    pub cycles: usize,
+}
+
+impl Clocked {
+    pub fn new(ram: Ram) -> Clocked {
+        Clocked { ram, cycles: 0 }
+    }
+}
+
+impl MappedHardware for Clocked {
+    fn tick(&mut self, cycles: usize) {
+        self.cycles += cycles;
+    }
+
+    fn read_byte(&mut self, address: u32) -> Option<u8> {
+        self.ram.read_byte(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+        self.ram.write_byte(address, value)
+    }
+
+    fn read_word(&mut self, address: u32) -> Option<u16> {
+        self.ram.read_word(address)
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+        self.ram.write_word(address, value)
+    }
+}
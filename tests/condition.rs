@@ -0,0 +1,83 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_condition {
+    use common::{boot, PROGRAM};
+
+    const X: u8 = 0b1_0000;
+    const N: u8 = 0b0_1000;
+    const Z: u8 = 0b0_0100;
+    const V: u8 = 0b0_0010;
+    const C: u8 = 0b0_0001;
+
+    /// The 68000 condition table, indexed by the condition field of the
+    /// opcode.
+    fn expected(condition: u16, ccr: u8) -> bool {
+        let c = ccr & C != 0;
+        let v = ccr & V != 0;
+        let z = ccr & Z != 0;
+        let n = ccr & N != 0;
+        match condition {
+            0x0 => true,
+            0x1 => false,
+            0x2 => !(c || z),
+            0x3 => c || z,
+            0x4 => !c,
+            0x5 => c,
+            0x6 => !z,
+            0x7 => z,
+            0x8 => !v,
+            0x9 => v,
+            0xa => !n,
+            0xb => n,
+            0xc => !(n ^ v),
+            0xd => n ^ v,
+            0xe => !(z || (n ^ v)),
+            0xf => z || (n ^ v),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_every_condition_with_every_ccr() {
+        for condition in 0..16 {
+            for ccr in 0..=(X | N | Z | V | C) {
+                // scc d0
+                let (mut cpu, mut ram) = boot(&[0x50C0 | condition << 8]);
+                cpu.registers.set_data(0, 0x5a);
+                cpu.registers.set_ccr(ccr);
+
+                cpu.execute_next_instruction(&mut ram);
+
+                let result = if expected(condition, ccr) { 0xff } else { 0x00 };
+                assert_eq!(
+                    cpu.registers.data(0),
+                    result,
+                    "condition {:x} with ccr {:05b}",
+                    condition,
+                    ccr
+                );
+                assert_eq!(cpu.registers.sr() as u8, ccr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_branch_on_condition() {
+        // bls.s *+4
+        let (mut cpu, mut ram) = boot(&[0x6302]);
+        cpu.registers.set_ccr(C);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 4);
+
+        // bls.s *+4 with N set only is not taken
+        let (mut cpu, mut ram) = boot(&[0x6302]);
+        cpu.registers.set_ccr(N);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 2);
+    }
+}
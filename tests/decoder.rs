@@ -43,6 +43,21 @@ mod test_m68k {
         );
     }
 
+    #[test]
+    fn test_decode_divs_w() {
+        // divs.w d1,d0
+        let opcode = 0b1000_000111_000001;
+        let instruction = decode(opcode);
+        assert_eq!(
+            instruction,
+            Instruction::DIVS(
+                DataSize::Word,
+                AddressingMode::DataDirect(1),
+                AddressingMode::DataDirect(0),
+            )
+        );
+    }
+
     #[test]
     fn test_decode_or_z() {
         // or.z a,Dd
@@ -95,6 +110,101 @@ mod test_m68k {
                 AddressingMode::DataDirect(0b010)
             )
         );
+
+        let opcode = 0b0000_011111_010010;
+        let instruction = decode(opcode);
+        assert_eq!(
+            instruction,
+            Instruction::BSET(
+                DataSize::Byte,
+                AddressingMode::DataDirect(0b011),
+                AddressingMode::AddressIndirect(0b010)
+            )
+        );
+    }
+
+    #[test]
+    fn test_decode_line_a_and_line_f() {
+        assert_eq!(decode(0xA123), Instruction::LineA(0xA123));
+        assert_eq!(decode(0xF200), Instruction::LineF(0xF200));
+    }
+
+    #[test]
+    fn test_decode_illegal() {
+        // illegal
+        assert_eq!(decode(0x4AFC), Instruction::Illegal);
+        // ori.? with size bits 11
+        assert_eq!(decode(0b0000_000011_000000), Instruction::Illegal);
+        // chk.l is 68020 only
+        assert_eq!(decode(0b0100_000100_000000), Instruction::Illegal);
+        // rtd is 68010 only
+        assert_eq!(decode(0x4E74), Instruction::Illegal);
+    }
+
+    #[test]
+    fn test_decode_invalid_effective_address() {
+        // bset #0,#imm
+        assert_eq!(decode(0x08FC), Instruction::Illegal);
+        // bchg d1,#imm
+        assert_eq!(decode(0x037C), Instruction::Illegal);
+        // clr.w (d16,pc)
+        assert_eq!(decode(0x427A), Instruction::Illegal);
+        // add.w d0,(d16,pc)
+        assert_eq!(decode(0xD17A), Instruction::Illegal);
+        // addq.b #1,a0
+        assert_eq!(decode(0x5208), Instruction::Illegal);
+        // move.b a0,d0
+        assert_eq!(decode(0x1008), Instruction::Illegal);
+        // lea d0,a0
+        assert_eq!(decode(0x41C0), Instruction::Illegal);
+
+        // btst d1,#imm only reads its operand
+        assert_eq!(
+            decode(0x033C),
+            Instruction::BTST(
+                DataSize::Byte,
+                AddressingMode::DataDirect(1),
+                AddressingMode::Immediate
+            )
+        );
     }
 
+    #[test]
+    fn test_decode_logic_to_status() {
+        assert_eq!(
+            decode(0x007C),
+            Instruction::ORI(
+                DataSize::Word,
+                AddressingMode::Immediate,
+                AddressingMode::SR
+            )
+        );
+        assert_eq!(
+            decode(0x023C),
+            Instruction::ANDI(
+                DataSize::Byte,
+                AddressingMode::Immediate,
+                AddressingMode::CCR
+            )
+        );
+        // there is no long form
+        assert_eq!(decode(0x0ABC), Instruction::Illegal);
+        // nor an immediate destination for arithmetic
+        assert_eq!(decode(0x067C), Instruction::Illegal);
+    }
+
+    #[test]
+    fn test_decode_eor() {
+        // eor.w d1,d2
+        let opcode = 0b1011_001101_000010;
+        let instruction = decode(opcode);
+        assert_eq!(
+            instruction,
+            Instruction::EOR(
+                DataSize::Word,
+                AddressingMode::DataDirect(1),
+                AddressingMode::DataDirect(2),
+            )
+        );
+    }
 }
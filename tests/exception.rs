@@ -0,0 +1,411 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_exception {
+    use common::{boot, Fixture, Ram, PROGRAM, STACK};
+    use m68k::bus::{Bus, UnmappedAccess};
+    use m68k::cpu::{AccessFault, Cpu, CpuState, Exception};
+    use m68k::mapped_hardware::MappedHardware;
+
+    const NOP: u16 = 0x4E71;
+    const HANDLER: u32 = 0x3000;
+
+    #[test]
+    fn test_short_frame() {
+        let (mut cpu, mut ram) = boot(&[NOP]);
+        ram.set_vector(35, HANDLER);
+        cpu.raise_exception(Exception::Trap(3));
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Trap(3)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sp(), STACK - 6);
+        assert_eq!(ram.read_word(STACK - 6), Some(0x2700));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_address_error_on_odd_read() {
+        // move.w (a0),d0
+        let (mut cpu, mut ram) = boot(&[0x3010]);
+        ram.set_vector(3, HANDLER);
+        cpu.registers.set_address(0, 0x1235);
+        cpu.registers.set_data(0, 0xdead_beef);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        let fault = AccessFault {
+            address: 0x1235,
+            read: true,
+            instruction: true,
+            function_code: 5,
+        };
+        assert_eq!(exception, Some(Exception::AddressError(fault)));
+        assert_eq!(cpu.registers.data(0), 0xdead_beef);
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sp(), STACK - 14);
+        assert_eq!(ram.read_word(STACK - 14), Some(0b1_0101));
+        assert_eq!(ram.read_long(STACK - 12), Some(0x1235));
+        assert_eq!(ram.read_word(STACK - 8), Some(0x3010));
+        assert_eq!(ram.read_word(STACK - 6), Some(0x2700));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_faulting_instruction_leaves_flags() {
+        // add.w (a0),d0
+        // clr.w (a0)
+        // not.w (a0)
+        // tst.w (a0)
+        for opcode in &[0xD050, 0x4250, 0x4650, 0x4A50] {
+            let (mut cpu, mut ram) = boot(&[*opcode]);
+            ram.set_vector(3, HANDLER);
+            cpu.registers.set_address(0, 0x1235);
+
+            let exception = cpu.execute_next_instruction(&mut ram);
+
+            assert!(matches!(exception, Some(Exception::AddressError(_))));
+            assert_eq!(ram.read_word(STACK - 6), Some(0x2700), "{:04X}", opcode);
+        }
+    }
+
+    #[test]
+    fn test_address_error_on_odd_write() {
+        // move.l d0,(a0)
+        let (mut cpu, mut ram) = boot(&[0x2080]);
+        ram.set_vector(3, HANDLER);
+        cpu.registers.set_address(0, 0x1001);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_word(STACK - 14), Some(0b0_0101));
+        assert_eq!(ram.read_long(STACK - 12), Some(0x1001));
+    }
+
+    #[test]
+    fn test_address_error_on_odd_fetch() {
+        // jmp (a0)
+        let (mut cpu, mut ram) = boot(&[0x4ED0]);
+        ram.set_vector(3, HANDLER);
+        cpu.registers.set_address(0, 0x2001);
+
+        cpu.execute_next_instruction(&mut ram);
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        let fault = AccessFault {
+            address: 0x2001,
+            read: true,
+            instruction: true,
+            function_code: 6,
+        };
+        assert_eq!(exception, Some(Exception::AddressError(fault)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_long(STACK - 4), Some(0x2001));
+    }
+
+    #[test]
+    fn test_bus_error_on_unmapped_read() {
+        // move.w (a0),d0
+        let (mut cpu, mut ram) = boot(&[0x3010]);
+        ram.set_vector(2, HANDLER);
+        cpu.registers.set_address(0, 0x2_0000);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        let fault = AccessFault {
+            address: 0x2_0000,
+            read: true,
+            instruction: true,
+            function_code: 5,
+        };
+        assert_eq!(exception, Some(Exception::BusError(fault)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_word(STACK - 14), Some(0b1_0101));
+        assert_eq!(ram.read_long(STACK - 12), Some(0x2_0000));
+    }
+
+    #[test]
+    fn test_open_bus() {
+        // move.w (a0),d0
+        let ram = Ram::with_program(&[0x3010]);
+        let mut bus = Bus::default();
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.set_unmapped_access(UnmappedAccess::OpenBus(0xffff));
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0x2_0000);
+
+        let exception = cpu.execute_next_instruction(&mut bus);
+
+        assert_eq!(exception, None);
+        assert_eq!(cpu.registers.data(0), 0xffff);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 2);
+    }
+
+    #[test]
+    fn test_line_a_trap() {
+        let (mut cpu, mut ram) = boot(&[0xA123]);
+        ram.set_vector(10, HANDLER);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::LineA));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+    }
+
+    #[test]
+    fn test_line_f_trap() {
+        let (mut cpu, mut ram) = boot(&[0xF200]);
+        ram.set_vector(11, HANDLER);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::LineF));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+    }
+
+    #[test]
+    fn test_illegal_instruction() {
+        // illegal
+        let (mut cpu, mut ram) = boot(&[0x4AFC]);
+        ram.set_vector(4, HANDLER);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::IllegalInstruction));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+    }
+
+    #[test]
+    fn test_illegal_addressing_mode() {
+        // move.w <mode 7, register 5>,d0
+        let (mut cpu, mut ram) = boot(&[0x303D]);
+        ram.set_vector(4, HANDLER);
+        cpu.registers.set_data(0, 0x1234);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::IllegalInstruction));
+        assert_eq!(cpu.registers.data(0), 0x1234);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+    }
+
+    #[test]
+    fn test_invalid_destination_has_no_side_effects() {
+        // move.l (a0)+,(d16,pc)
+        let (mut cpu, mut ram) = boot(&[0x25D8, 0x0010]);
+        ram.set_vector(4, HANDLER);
+        cpu.registers.set_address(0, 0x4000);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::IllegalInstruction));
+        assert_eq!(cpu.registers.address(0), 0x4000);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+    }
+
+    #[test]
+    fn test_privilege_violation_switches_stacks() {
+        // move.w #$0000,sr
+        // stop #$2700
+        let (mut cpu, mut ram) = boot(&[0x46FC, 0x0000, 0x4E72, 0x2700]);
+        ram.set_vector(8, HANDLER);
+        // rte
+        ram.load_words(HANDLER, &[0x4E73]);
+        cpu.registers.set_usp(0x800);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.sp(), 0x800);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::PrivilegeViolation));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sp(), STACK - 6);
+        assert_eq!(cpu.registers.usp(), 0x800);
+        assert_eq!(ram.read_word(STACK - 6), Some(0x0000));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 4));
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 4);
+        assert_eq!(cpu.registers.sp(), 0x800);
+        assert_eq!(cpu.registers.sr(), 0x0000);
+    }
+
+    #[test]
+    fn test_privileged_instructions() {
+        let programs: [&[u16]; 5] = [
+            // reset
+            &[0x4E70],
+            // rte
+            &[0x4E73],
+            // move a0,usp
+            &[0x4E60],
+            // ori.w #$0700,sr
+            &[0x007C, 0x0700],
+            // move.w d0,sr
+            &[0x46C0],
+        ];
+        for program in programs.iter() {
+            let (mut cpu, mut ram) = boot(program);
+            ram.set_vector(8, HANDLER);
+            cpu.registers.set_complete_ccr(0x0000);
+
+            let exception = cpu.execute_next_instruction(&mut ram);
+
+            assert_eq!(exception, Some(Exception::PrivilegeViolation));
+            assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM));
+        }
+    }
+
+    #[test]
+    fn test_trace() {
+        let (mut cpu, mut ram) = boot(&[NOP, NOP]);
+        ram.set_vector(9, HANDLER);
+        cpu.registers.set_complete_ccr(0xA700);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Trace));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sr(), 0x2700);
+        assert_eq!(ram.read_word(STACK - 6), Some(0xA700));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_trace_is_sampled_before_instruction() {
+        // move.w #$a700,sr
+        let (mut cpu, mut ram) = boot(&[0x46FC, 0xA700, NOP]);
+        ram.set_vector(9, HANDLER);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::Trace)
+        );
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 6));
+    }
+
+    #[test]
+    fn test_trace_after_trap() {
+        let (mut cpu, mut ram) = boot(&[NOP]);
+        ram.set_vector(32, HANDLER);
+        ram.set_vector(9, HANDLER + 0x100);
+        cpu.registers.set_complete_ccr(0xA700);
+        cpu.raise_exception(Exception::Trap(0));
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Trap(0)));
+        assert_eq!(cpu.registers.pc(), HANDLER + 0x100);
+        assert_eq!(cpu.registers.sp(), STACK - 12);
+        assert_eq!(ram.read_word(STACK - 12), Some(0x2700));
+        assert_eq!(ram.read_long(STACK - 10), Some(HANDLER));
+        assert_eq!(ram.read_word(STACK - 6), Some(0xA700));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_no_trace_after_illegal_instruction() {
+        let (mut cpu, mut ram) = boot(&[0x4AFC]);
+        ram.set_vector(4, HANDLER);
+        cpu.registers.set_complete_ccr(0xA700);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::IllegalInstruction));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sp(), STACK - 6);
+    }
+
+    #[test]
+    fn test_traced_stop_does_not_stop() {
+        // stop #$2700
+        let (mut cpu, mut ram) = boot(&[0x4E72, 0x2700]);
+        ram.set_vector(9, HANDLER);
+        ram.load_words(HANDLER, &[NOP]);
+        cpu.registers.set_complete_ccr(0xA700);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Trace));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.pc(), HANDLER + 2);
+    }
+
+    #[test]
+    fn test_double_bus_fault_halts() {
+        // move.w (a0),d0
+        let (mut cpu, mut ram) = boot(&[0x3010]);
+        ram.set_vector(3, HANDLER);
+        cpu.registers.set_address(0, 0x1235);
+        cpu.registers.set_sp(0x0fff);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert!(exception.is_some());
+        assert_eq!(cpu.state(), CpuState::Halted);
+        let pc = cpu.registers.pc();
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), pc);
+
+        cpu.reset(&mut ram);
+        assert_eq!(cpu.state(), CpuState::Running);
+        assert_eq!(cpu.registers.pc(), PROGRAM);
+    }
+
+    #[test]
+    fn test_fault_on_reset_vectors_halts() {
+        let mut ram = Ram::new(4);
+        let mut cpu = Cpu::default();
+
+        cpu.reset(&mut ram);
+
+        assert_eq!(cpu.state(), CpuState::Halted);
+    }
+
+    #[test]
+    fn test_stop_state() {
+        // stop #$2000
+        let (mut cpu, mut ram) = boot(&[0x4E72, 0x2000]);
+        ram.set_vector(25, HANDLER);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.state(), CpuState::Stopped);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.state(), CpuState::Stopped);
+
+        cpu.request_auto_interrupt(1);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.state(), CpuState::Running);
+        assert_eq!(cpu.registers.pc(), HANDLER);
+    }
+
+    #[test]
+    fn test_group_priority() {
+        let (mut cpu, mut ram) = boot(&[NOP]);
+        ram.set_vector(2, HANDLER);
+        let fault = AccessFault {
+            address: 0,
+            read: false,
+            instruction: true,
+            function_code: 5,
+        };
+        cpu.raise_exception(Exception::ZeroDivide);
+        cpu.raise_exception(Exception::BusError(fault));
+        cpu.raise_exception(Exception::IllegalInstruction);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::BusError(fault)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+    }
+}
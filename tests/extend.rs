@@ -0,0 +1,114 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_extend {
+    use common::boot;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const X: u16 = 0b1_0000;
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+    const V: u16 = 0b0_0010;
+    const C: u16 = 0b0_0001;
+
+    fn ccr(cpu: &m68k::cpu::Cpu) -> u16 {
+        cpu.registers.sr() & 0x1f
+    }
+
+    #[test]
+    fn test_addx_multi_precision() {
+        // addx.l -(a0),-(a1) twice: 1_ffff_ffff + 1
+        let (mut cpu, mut ram) = boot(&[0xD388, 0xD388]);
+        ram.write_long(0x3000, 0x0000_0001);
+        ram.write_long(0x3004, 0xffff_ffff);
+        ram.write_long(0x3100, 0x0000_0000);
+        ram.write_long(0x3104, 0x0000_0001);
+        cpu.registers.set_address(0, 0x3008);
+        cpu.registers.set_address(1, 0x3108);
+        cpu.registers.set_ccr(Z as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ram.read_long(0x3104), Some(0));
+        assert_eq!(ccr(&cpu), X | Z | C);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ram.read_long(0x3100), Some(2));
+        assert_eq!(cpu.registers.address(0), 0x3000);
+        assert_eq!(cpu.registers.address(1), 0x3100);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_addx_overflow() {
+        // addx.l d0,d1
+        let (mut cpu, mut ram) = boot(&[0xD380]);
+        cpu.registers.set_data(0, 0);
+        cpu.registers.set_data(1, 0x7fff_ffff);
+        cpu.registers.set_ccr(X as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x8000_0000);
+        assert_eq!(ccr(&cpu), N | V);
+    }
+
+    #[test]
+    fn test_subx_borrow() {
+        // subx.l d0,d1
+        let (mut cpu, mut ram) = boot(&[0x9380]);
+        cpu.registers.set_data(0, 0);
+        cpu.registers.set_data(1, 0);
+        cpu.registers.set_ccr((X | Z) as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0xffff_ffff);
+        assert_eq!(ccr(&cpu), X | N | C);
+    }
+
+    #[test]
+    fn test_subx_zero_is_never_set() {
+        // subx.b -(a0),-(a1)
+        let (mut cpu, mut ram) = boot(&[0x9308]);
+        ram.write_word(0x3000, 0x0500);
+        ram.write_word(0x3100, 0x0500);
+        cpu.registers.set_address(0, 0x3001);
+        cpu.registers.set_address(1, 0x3101);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(ram.read_word(0x3100), Some(0x0000));
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_neg() {
+        // neg.w d0
+        let (mut cpu, mut ram) = boot(&[0x4440, 0x4440]);
+        cpu.registers.set_data(0, 0x8000);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x8000);
+        assert_eq!(ccr(&cpu), X | N | V | C);
+
+        cpu.registers.set_data(0, 0);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0);
+        assert_eq!(ccr(&cpu), Z);
+    }
+
+    #[test]
+    fn test_negx() {
+        // negx.l d0
+        let (mut cpu, mut ram) = boot(&[0x4080]);
+        cpu.registers.set_data(0, 0);
+        cpu.registers.set_ccr((X | Z) as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0xffff_ffff);
+        assert_eq!(ccr(&cpu), X | N | C);
+    }
+}
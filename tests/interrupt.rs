@@ -0,0 +1,281 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_interrupt {
+    use common::{boot, Fixture, Ram, PROGRAM, STACK};
+    use m68k::bus::Bus;
+    use m68k::cpu::Exception;
+    use m68k::interrupt::{InterruptController, InterruptLine};
+    use m68k::mapped_hardware::{InterruptAcknowledge, MappedHardware};
+
+    const NOP: u16 = 0x4E71;
+    const HANDLER: u32 = 0x3000;
+
+    /// RAM with a device that answers interrupt acknowledge cycles.
+    struct Device {
+        ram: Ram,
+        level: u8,
+        acknowledge: InterruptAcknowledge,
+    }
+
+    impl MappedHardware for Device {
+        fn interrupt_acknowledge(&mut self, level: u8) -> Option<InterruptAcknowledge> {
+            if level == self.level {
+                Some(self.acknowledge)
+            } else {
+                None
+            }
+        }
+
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            self.ram.read_word(address)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            self.ram.write_word(address, value)
+        }
+    }
+
+    #[test]
+    fn test_masked_interrupt_waits() {
+        // move.w #$2000,sr
+        let (mut cpu, mut ram) = boot(&[NOP, 0x46FC, 0x2000, NOP]);
+        ram.set_vector(27, HANDLER);
+        cpu.request_auto_interrupt(3);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 6);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Interrupt(27)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sr(), 0x2300);
+        assert_eq!(ram.read_word(STACK - 6), Some(0x2000));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 6));
+    }
+
+    #[test]
+    fn test_level_7_is_not_maskable() {
+        let (mut cpu, mut ram) = boot(&[NOP]);
+        ram.set_vector(31, HANDLER);
+        cpu.request_auto_interrupt(7);
+
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::Interrupt(31))
+        );
+        assert_eq!(cpu.registers.pc(), HANDLER);
+    }
+
+    #[test]
+    fn test_highest_level_first() {
+        let (mut cpu, mut ram) = boot(&[NOP]);
+        cpu.registers.set_complete_ccr(0x2000);
+        cpu.request_auto_interrupt(2);
+        cpu.request_interrupt(5, 0x40);
+
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::Interrupt(0x40))
+        );
+        assert_eq!(cpu.registers.sr(), 0x2500);
+    }
+
+    #[test]
+    fn test_interrupt_acknowledge() {
+        let responses = [
+            (
+                InterruptAcknowledge::Vector(0x40),
+                Exception::Interrupt(0x40),
+            ),
+            (InterruptAcknowledge::Autovector, Exception::Interrupt(28)),
+            (
+                InterruptAcknowledge::Vector(0x0f),
+                Exception::UninitializedInterrupt,
+            ),
+            (InterruptAcknowledge::Spurious, Exception::SpuriousInterrupt),
+        ];
+        for &(acknowledge, expected) in responses.iter() {
+            let (mut cpu, mut ram) = boot(&[NOP]);
+            ram.set_vector(expected.vector(), HANDLER);
+            let mut device = Device {
+                ram,
+                level: 4,
+                acknowledge,
+            };
+            cpu.request_interrupt(4, 0x50);
+
+            assert_eq!(cpu.execute_next_instruction(&mut device), None);
+            cpu.registers.set_complete_ccr(0x2000);
+            assert_eq!(cpu.execute_next_instruction(&mut device), Some(expected));
+            assert_eq!(cpu.registers.pc(), HANDLER);
+        }
+    }
+
+    #[test]
+    fn test_stop_wakes_on_unmasked_interrupt() {
+        // stop #$2500
+        let (mut cpu, mut ram) = boot(&[0x4E72, 0x2500]);
+        ram.set_vector(30, HANDLER);
+        cpu.execute_next_instruction(&mut ram);
+
+        cpu.request_auto_interrupt(3);
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 4);
+
+        cpu.request_auto_interrupt(6);
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::Interrupt(30))
+        );
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 4));
+    }
+
+    /// RAM that ends right after the STOP instruction and counts the reads
+    /// past it.
+    struct Edge {
+        ram: Ram,
+        reads_past_end: usize,
+        cycles: usize,
+    }
+
+    impl MappedHardware for Edge {
+        fn tick(&mut self, cycles: usize) {
+            self.cycles += cycles;
+        }
+
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            if (PROGRAM + 4..HANDLER).contains(&address) {
+                self.reads_past_end += 1;
+                return None;
+            }
+            self.ram.read_word(address)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            self.ram.write_word(address, value)
+        }
+    }
+
+    #[test]
+    fn test_stopped_cpu_runs_no_bus_cycles() {
+        // stop #$2500
+        let (mut cpu, mut ram) = boot(&[0x4E72, 0x2500]);
+        ram.set_vector(30, HANDLER);
+        let mut edge = Edge {
+            ram,
+            reads_past_end: 0,
+            cycles: 0,
+        };
+        cpu.execute_next_instruction(&mut edge);
+
+        for _ in 0..3 {
+            assert_eq!(cpu.execute_next_instruction(&mut edge), None);
+        }
+        assert_eq!(edge.reads_past_end, 0);
+        assert_eq!(edge.cycles, 12);
+
+        cpu.request_auto_interrupt(6);
+        assert_eq!(
+            cpu.execute_next_instruction(&mut edge),
+            Some(Exception::Interrupt(30))
+        );
+        assert_eq!(cpu.registers.pc(), HANDLER);
+    }
+
+    /// Raises a level 5 autovectored interrupt every 100 cycles.
+    struct Timer {
+        line: InterruptLine,
+        cycles: usize,
+    }
+
+    impl MappedHardware for Timer {
+        fn tick(&mut self, cycles: usize) {
+            self.cycles += cycles;
+            if self.cycles >= 100 {
+                self.line.assert(5);
+            }
+        }
+
+        fn interrupt_acknowledge(&mut self, level: u8) -> Option<InterruptAcknowledge> {
+            if level == 5 && self.line.level() == 5 {
+                self.line.deassert();
+                self.cycles = 0;
+                Some(InterruptAcknowledge::Autovector)
+            } else {
+                None
+            }
+        }
+
+        fn read_word(&mut self, _address: u32) -> Option<u16> {
+            None
+        }
+
+        fn write_word(&mut self, _address: u32, _value: u16) -> Option<u16> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_device_interrupt_line() {
+        // bra.s *
+        let (mut cpu, mut ram) = boot(&[0x60FE]);
+        ram.set_vector(29, HANDLER);
+        ram.load_words(HANDLER, &[0x60FE]);
+        let mut controller = InterruptController::default();
+        let timer = Timer {
+            line: controller.line(),
+            cycles: 0,
+        };
+        let mut bus = Bus::default();
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0, 0, None, Box::new(timer)).unwrap();
+        cpu.registers.set_complete_ccr(0x2000);
+
+        let mut taken = None;
+        for _ in 0..100 {
+            cpu.set_interrupt_level(controller.level());
+            taken = cpu.execute_next_instruction(&mut bus);
+            if taken.is_some() {
+                break;
+            }
+        }
+
+        assert_eq!(taken, Some(Exception::Interrupt(29)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(controller.level(), 0);
+    }
+
+    #[test]
+    fn test_level_7_line_is_edge_triggered() {
+        let (mut cpu, mut ram) = boot(&[NOP, NOP, NOP]);
+        ram.set_vector(24, HANDLER);
+        ram.load_words(HANDLER, &[NOP, NOP, NOP]);
+        let mut controller = InterruptController::default();
+        let line = controller.line();
+
+        line.assert(7);
+        cpu.set_interrupt_level(controller.level());
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::SpuriousInterrupt)
+        );
+
+        cpu.set_interrupt_level(controller.level());
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+
+        line.deassert();
+        cpu.set_interrupt_level(controller.level());
+        line.assert(7);
+        cpu.set_interrupt_level(controller.level());
+        assert_eq!(
+            cpu.execute_next_instruction(&mut ram),
+            Some(Exception::SpuriousInterrupt)
+        );
+    }
+}
@@ -0,0 +1,71 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_logic {
+    use common::boot;
+
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+
+    fn ccr(cpu: &m68k::cpu::Cpu) -> u16 {
+        cpu.registers.sr() & 0x1f
+    }
+
+    #[test]
+    fn test_eor_word() {
+        // eor.w d1,d2
+        let (mut cpu, mut ram) = boot(&[0xB342]);
+        cpu.registers.set_data(1, 0x1234);
+        cpu.registers.set_data(2, 0xffff_0000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(2), 0xffff_1234);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_eor_long() {
+        // eor.l d1,d2
+        let (mut cpu, mut ram) = boot(&[0xB382]);
+        cpu.registers.set_data(1, 0x8000_1234);
+        cpu.registers.set_data(2, 0x0000_1234);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(2), 0x8000_0000);
+        assert_eq!(ccr(&cpu), N);
+    }
+
+    #[test]
+    fn test_or_word() {
+        // or.w d1,d2
+        let (mut cpu, mut ram) = boot(&[0x8441]);
+        cpu.registers.set_data(1, 0x1234);
+        cpu.registers.set_data(2, 0xffff_0000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(2), 0xffff_1234);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_or_long() {
+        // or.l d1,d2
+        // or.l d3,d4
+        let (mut cpu, mut ram) = boot(&[0x8481, 0x8883]);
+        cpu.registers.set_data(1, 0x8000_0000);
+        cpu.registers.set_data(2, 0x0012_3400);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(2), 0x8012_3400);
+        assert_eq!(ccr(&cpu), N);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(4), 0);
+        assert_eq!(ccr(&cpu), Z);
+    }
+}
@@ -0,0 +1,79 @@
+extern crate m68k;
+
+#[cfg(test)]
+mod test_memory {
+    use m68k::bus::Bus;
+    use m68k::cpu::{Cpu, Exception};
+    use m68k::mapped_hardware::MappedHardware;
+    use m68k::memory::{Ram, Rom, RomWrite};
+
+    #[test]
+    fn test_ram_starts_zeroed() {
+        let mut ram = Ram::new(0x1_0000);
+
+        assert_eq!(ram.read_word(0x8000), Some(0));
+        assert_eq!(ram.write_word(0x8000, 0x1234), Some(0x1234));
+        assert_eq!(ram.read_word(0x8000), Some(0x1234));
+        assert_eq!(ram.read_byte(0x8001), Some(0x34));
+    }
+
+    #[test]
+    fn test_ram_is_bounded_by_size() {
+        let mut ram = Ram::new(0x100);
+
+        assert_eq!(ram.read_word(0xfe), Some(0));
+        assert_eq!(ram.read_word(0x100), None);
+        assert_eq!(ram.write_word(0xff, 0), None);
+        assert_eq!(ram.write_byte(0x100, 0), None);
+    }
+
+    #[test]
+    fn test_many_large_rams() {
+        let rams: Vec<Ram> = (0..64).map(|_| Ram::new(0x100_0000)).collect();
+
+        assert!(rams.iter().all(|ram| ram.size() == 0x100_0000));
+    }
+
+    #[test]
+    fn test_rom_ignores_writes() {
+        let mut rom = Rom::new(vec![0x4e, 0x71], RomWrite::Ignore);
+
+        assert_eq!(rom.write_word(0, 0xffff), Some(0xffff));
+        assert_eq!(rom.read_word(0), Some(0x4e71));
+        assert_eq!(rom.read_word(2), None);
+    }
+
+    #[test]
+    fn test_rom_write_bus_error() {
+        let mut rom = Rom::new(vec![0x4e, 0x71], RomWrite::BusError);
+
+        assert_eq!(rom.write_word(0, 0xffff), None);
+        assert_eq!(rom.write_byte(1, 0xff), None);
+        assert_eq!(rom.read_word(0), Some(0x4e71));
+    }
+
+    #[test]
+    fn test_boot_from_rom() {
+        // move.w d0,(a0)
+        let image = vec![0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x08, 0x30, 0x80];
+        let mut bus = Bus::default();
+        bus.map_hardware(
+            0,
+            0x100,
+            None,
+            Box::new(Rom::new(image, RomWrite::BusError)),
+        )
+        .unwrap();
+        bus.map_hardware(0x1000, 0x1000, None, Box::new(Ram::new(0x1000)))
+            .unwrap();
+        let mut cpu = Cpu::default();
+        cpu.reset(&mut bus);
+        cpu.registers.set_address(0, 0x4);
+
+        match cpu.execute_next_instruction(&mut bus) {
+            Some(Exception::BusError(fault)) => assert_eq!(fault.address, 0x4),
+            exception => panic!("expected a bus error, got {:?}", exception),
+        }
+        assert_eq!(bus.read_long(0x4), Some(0x8));
+    }
+}
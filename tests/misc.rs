@@ -0,0 +1,166 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_misc {
+    use common::{boot, Ram};
+    use m68k::mapped_hardware::MappedHardware;
+
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+
+    fn ccr(cpu: &m68k::cpu::Cpu) -> u16 {
+        cpu.registers.sr() & 0x1f
+    }
+
+    /// RAM that logs the bus cycles seen by a semaphore byte at 0x3000.
+    struct SharedRam {
+        ram: Ram,
+        cycles: Vec<&'static str>,
+    }
+
+    impl MappedHardware for SharedRam {
+        fn set_locked(&mut self, locked: bool) {
+            self.cycles.push(if locked { "lock" } else { "unlock" });
+        }
+
+        fn read_byte(&mut self, address: u32) -> Option<u8> {
+            if address == 0x3000 {
+                self.cycles.push("read");
+            }
+            self.ram.read_byte(address)
+        }
+
+        fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+            if address == 0x3000 {
+                self.cycles.push("write");
+            }
+            self.ram.write_byte(address, value)
+        }
+
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            self.ram.read_word(address)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            self.ram.write_word(address, value)
+        }
+    }
+
+    #[test]
+    fn test_exg() {
+        // exg d0,d1
+        // exg d2,a3
+        let (mut cpu, mut ram) = boot(&[0xC141, 0xC58B]);
+        cpu.registers.set_data(0, 0x1111_1111);
+        cpu.registers.set_data(1, 0x2222_2222);
+        cpu.registers.set_data(2, 0x3333_3333);
+        cpu.registers.set_address(3, 0x4444_4444);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x2222_2222);
+        assert_eq!(cpu.registers.data(1), 0x1111_1111);
+        assert_eq!(cpu.registers.data(2), 0x4444_4444);
+        assert_eq!(cpu.registers.address(3), 0x3333_3333);
+    }
+
+    #[test]
+    fn test_ext() {
+        // ext.w d0
+        // ext.l d0
+        let (mut cpu, mut ram) = boot(&[0x4880, 0x48C0]);
+        cpu.registers.set_data(0, 0x1234_5680);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x1234_ff80);
+        assert_eq!(ccr(&cpu), N);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0xffff_ff80);
+        assert_eq!(ccr(&cpu), N);
+    }
+
+    #[test]
+    fn test_swap() {
+        // swap d0
+        let (mut cpu, mut ram) = boot(&[0x4840]);
+        cpu.registers.set_data(0, 0x1234_5678);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x5678_1234);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_tas_is_a_locked_cycle() {
+        // tas (a0)
+        // tas (a0)
+        let (mut cpu, ram) = boot(&[0x4AD0, 0x4AD0]);
+        let mut shared = SharedRam {
+            ram,
+            cycles: Vec::new(),
+        };
+        cpu.registers.set_address(0, 0x3000);
+
+        cpu.execute_next_instruction(&mut shared);
+        assert_eq!(shared.ram.read_byte(0x3000), Some(0x80));
+        assert_eq!(ccr(&cpu), Z);
+        assert_eq!(shared.cycles, vec!["lock", "read", "write", "unlock"]);
+
+        cpu.execute_next_instruction(&mut shared);
+        assert_eq!(ccr(&cpu), N);
+    }
+
+    #[test]
+    fn test_tas_register() {
+        // tas d0
+        let (mut cpu, mut ram) = boot(&[0x4AC0]);
+        cpu.registers.set_data(0, 0x0101);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x0181);
+        assert_eq!(ccr(&cpu), 0);
+    }
+
+    #[test]
+    fn test_scc() {
+        // st d0
+        // sf (a0)
+        // seq d1
+        let (mut cpu, mut ram) = boot(&[0x50C0, 0x51D0, 0x57C1]);
+        cpu.registers.set_data(0, 0x1234_5600);
+        cpu.registers.set_data(1, 0x1234_5678);
+        cpu.registers.set_address(0, 0x3000);
+        ram.write_word(0x3000, 0xaaaa);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x1234_56ff);
+        assert_eq!(ram.read_word(0x3000), Some(0x00aa));
+        assert_eq!(cpu.registers.data(1), 0x1234_5600);
+    }
+
+    #[test]
+    fn test_link_unlk() {
+        // link a6,#-8
+        // unlk a6
+        let (mut cpu, mut ram) = boot(&[0x4E56, 0xFFF8, 0x4E5E]);
+        cpu.registers.set_address(6, 0x1234_5678);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.address(6), 0x0ffc);
+        assert_eq!(cpu.registers.sp(), 0x0ff4);
+        assert_eq!(ram.read_long(0x0ffc), Some(0x1234_5678));
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.address(6), 0x1234_5678);
+        assert_eq!(cpu.registers.sp(), 0x1000);
+    }
+}
@@ -0,0 +1,104 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_movep {
+    use common::{boot, Ram};
+    use m68k::mapped_hardware::MappedHardware;
+
+    const PERIPHERAL: u32 = 0x8000;
+
+    /// RAM with an 8-bit peripheral above `PERIPHERAL` that logs its byte
+    /// accesses and counts any word accesses it sees.
+    struct Board {
+        ram: Ram,
+        registers: [u8; 16],
+        writes: Vec<(u32, u8)>,
+        word_accesses: usize,
+    }
+
+    impl Board {
+        fn new(ram: Ram) -> Board {
+            Board {
+                ram,
+                registers: [0; 16],
+                writes: Vec::new(),
+                word_accesses: 0,
+            }
+        }
+    }
+
+    impl MappedHardware for Board {
+        fn read_byte(&mut self, address: u32) -> Option<u8> {
+            if address >= PERIPHERAL {
+                Some(self.registers[(address - PERIPHERAL) as usize])
+            } else {
+                self.ram.read_byte(address)
+            }
+        }
+
+        fn write_byte(&mut self, address: u32, value: u8) -> Option<u16> {
+            if address >= PERIPHERAL {
+                self.writes.push((address, value));
+                self.registers[(address - PERIPHERAL) as usize] = value;
+                Some(value as u16)
+            } else {
+                self.ram.write_byte(address, value)
+            }
+        }
+
+        fn read_word(&mut self, address: u32) -> Option<u16> {
+            if address >= PERIPHERAL {
+                self.word_accesses += 1;
+            }
+            self.ram.read_word(address)
+        }
+
+        fn write_word(&mut self, address: u32, value: u16) -> Option<u16> {
+            if address >= PERIPHERAL {
+                self.word_accesses += 1;
+            }
+            self.ram.write_word(address, value)
+        }
+    }
+
+    #[test]
+    fn test_movep_long_to_memory() {
+        // movep.l d0,1(a0)
+        let (mut cpu, ram) = boot(&[0x01C8, 0x0001]);
+        let mut board = Board::new(ram);
+        cpu.registers.set_address(0, PERIPHERAL);
+        cpu.registers.set_data(0, 0x1234_5678);
+
+        cpu.execute_next_instruction(&mut board);
+
+        assert_eq!(
+            board.writes,
+            vec![
+                (PERIPHERAL + 1, 0x12),
+                (PERIPHERAL + 3, 0x34),
+                (PERIPHERAL + 5, 0x56),
+                (PERIPHERAL + 7, 0x78),
+            ]
+        );
+        assert_eq!(board.word_accesses, 0);
+    }
+
+    #[test]
+    fn test_movep_word_from_memory() {
+        // movep.w -2(a0),d1
+        let (mut cpu, ram) = boot(&[0x0308, 0xfffe]);
+        let mut board = Board::new(ram);
+        board.registers[2] = 0xab;
+        board.registers[3] = 0xff;
+        board.registers[4] = 0xcd;
+        cpu.registers.set_address(0, PERIPHERAL + 4);
+        cpu.registers.set_data(1, 0x1234_5678);
+
+        cpu.execute_next_instruction(&mut board);
+
+        assert_eq!(cpu.registers.data(1), 0x1234_abcd);
+        assert_eq!(board.word_accesses, 0);
+    }
+}
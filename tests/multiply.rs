@@ -0,0 +1,117 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_multiply {
+    use common::{boot, Clocked, Fixture, PROGRAM, STACK};
+    use m68k::cpu::Exception;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+    const V: u16 = 0b0_0010;
+    const C: u16 = 0b0_0001;
+
+    const MULU: u16 = 0xC2C0; // mulu.w d0,d1
+    const MULS: u16 = 0xC3C0; // muls.w d0,d1
+    const DIVU: u16 = 0x82C0; // divu.w d0,d1
+    const DIVS: u16 = 0x83C0; // divs.w d0,d1
+
+    #[test]
+    fn test_mulu() {
+        let (mut cpu, ram) = boot(&[MULU]);
+        let mut ram = Clocked::new(ram);
+        cpu.registers.set_data(0, 0x1234_ffff);
+        cpu.registers.set_data(1, 0xffff_0003);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x0002_fffd);
+        assert_eq!(cpu.registers.sr() & (N | Z | V | C), 0);
+        assert_eq!(ram.cycles, 34 + 2 * 16);
+    }
+
+    #[test]
+    fn test_muls() {
+        let (mut cpu, ram) = boot(&[MULS]);
+        let mut ram = Clocked::new(ram);
+        cpu.registers.set_data(0, 5);
+        cpu.registers.set_data(1, 0xfffd);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0xffff_fff1);
+        assert_eq!(cpu.registers.sr() & (N | Z | V | C), N);
+        // 0b101 with a zero below it has four 01/10 pairs
+        assert_eq!(ram.cycles, 34 + 2 * 4);
+    }
+
+    #[test]
+    fn test_divu() {
+        let (mut cpu, mut ram) = boot(&[DIVU]);
+        cpu.registers.set_data(0, 10);
+        cpu.registers.set_data(1, 100_007);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x0007_2710);
+        assert_eq!(cpu.registers.sr() & (N | Z | V | C), 0);
+    }
+
+    #[test]
+    fn test_divu_overflow() {
+        let (mut cpu, ram) = boot(&[DIVU]);
+        let mut ram = Clocked::new(ram);
+        cpu.registers.set_data(0, 1);
+        cpu.registers.set_data(1, 0x0010_0000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x0010_0000);
+        assert_eq!(cpu.registers.sr() & (V | C), V);
+        assert_eq!(ram.cycles, 10 - 4);
+    }
+
+    #[test]
+    fn test_divs() {
+        let (mut cpu, mut ram) = boot(&[DIVS]);
+        cpu.registers.set_data(0, 2);
+        cpu.registers.set_data(1, -7i32 as u32);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        // quotient -3, remainder takes the sign of the dividend
+        assert_eq!(cpu.registers.data(1), 0xffff_fffd);
+        assert_eq!(cpu.registers.sr() & (N | Z | V | C), N);
+    }
+
+    #[test]
+    fn test_divs_overflow() {
+        let (mut cpu, mut ram) = boot(&[DIVS]);
+        cpu.registers.set_data(0, 0xffff);
+        cpu.registers.set_data(1, 0x8000_0000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(1), 0x8000_0000);
+        assert_eq!(cpu.registers.sr() & (V | C), V);
+    }
+
+    #[test]
+    fn test_divide_by_zero() {
+        let (mut cpu, mut ram) = boot(&[DIVU]);
+        ram.set_vector(5, 0x3000);
+        cpu.registers.set_data(0, 0);
+        cpu.registers.set_data(1, 1234);
+        cpu.registers.set_ccr(C as u8);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::ZeroDivide));
+        assert_eq!(cpu.registers.data(1), 1234);
+        assert_eq!(cpu.registers.sr() & C, 0);
+        assert_eq!(cpu.registers.pc(), 0x3000);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+}
@@ -0,0 +1,86 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_reset {
+    use common::{boot, Fixture, Ram, PROGRAM, STACK};
+    use m68k::bus::Bus;
+    use m68k::cpu::Cpu;
+    use m68k::mapped_hardware::MappedHardware;
+    use m68k::vm::VirtualMachine;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Counts how often the reset line was asserted.
+    struct Peripheral {
+        resets: Rc<Cell<usize>>,
+    }
+
+    impl MappedHardware for Peripheral {
+        fn reset(&mut self) {
+            self.resets.set(self.resets.get() + 1);
+        }
+
+        fn read_word(&mut self, _address: u32) -> Option<u16> {
+            None
+        }
+
+        fn write_word(&mut self, _address: u32, _value: u16) -> Option<u16> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_reset_sequence() {
+        let mut ram = Ram::with_program(&[]);
+        let mut cpu = Cpu::default();
+        cpu.registers.set_complete_ccr(0x801f);
+        cpu.registers.set_sp(0x800);
+
+        cpu.reset(&mut ram);
+
+        assert_eq!(cpu.registers.sr(), 0x2700);
+        assert_eq!(cpu.registers.sp(), STACK);
+        assert_eq!(cpu.registers.usp(), 0x800);
+        assert_eq!(cpu.registers.pc(), PROGRAM);
+    }
+
+    #[test]
+    fn test_reset_instruction() {
+        // reset
+        let (mut cpu, ram) = boot(&[0x4E70]);
+        let resets = Rc::new(Cell::new(0));
+        let mut bus = Bus::default();
+        let peripheral = Peripheral {
+            resets: resets.clone(),
+        };
+        bus.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        bus.map_hardware(0, 0, None, Box::new(peripheral)).unwrap();
+        cpu.registers.set_data(0, 0x1234);
+
+        assert_eq!(cpu.execute_next_instruction(&mut bus), None);
+
+        assert_eq!(resets.get(), 1);
+        assert!(bus.cycles >= 124);
+        assert_eq!(cpu.registers.data(0), 0x1234);
+        assert_eq!(cpu.registers.sr(), 0x2700);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 2);
+    }
+
+    #[test]
+    fn test_vm_init_resets_peripherals() {
+        let resets = Rc::new(Cell::new(0));
+        let mut vm = VirtualMachine::new(vec![]);
+        let peripheral = Peripheral {
+            resets: resets.clone(),
+        };
+        let ram = Ram::with_program(&[0x4E71]);
+        vm.map_hardware(0, 0x1_0000, None, Box::new(ram)).unwrap();
+        vm.map_hardware(0, 0, None, Box::new(peripheral)).unwrap();
+
+        vm.init();
+
+        assert_eq!(resets.get(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_shift {
+    use common::boot;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const X: u16 = 0b1_0000;
+    const N: u16 = 0b0_1000;
+    const Z: u16 = 0b0_0100;
+    const V: u16 = 0b0_0010;
+    const C: u16 = 0b0_0001;
+
+    fn ccr(cpu: &m68k::cpu::Cpu) -> u16 {
+        cpu.registers.sr() & 0x1f
+    }
+
+    #[test]
+    fn test_asl_overflow() {
+        // asl.b #1,d0
+        let (mut cpu, mut ram) = boot(&[0xE300, 0xE300]);
+        cpu.registers.set_data(0, 0x40);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x80);
+        assert_eq!(ccr(&cpu), N | V);
+
+        cpu.registers.set_data(0, 0xC0);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x80);
+        assert_eq!(ccr(&cpu), X | N | C);
+    }
+
+    #[test]
+    fn test_asl_overflow_on_any_sign_change() {
+        // asl.b #2,d0: 0x20 passes through 0x40 but ends negative
+        let (mut cpu, mut ram) = boot(&[0xE500]);
+        cpu.registers.set_data(0, 0x20);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x80);
+        assert_eq!(ccr(&cpu), N | V);
+    }
+
+    #[test]
+    fn test_asr_immediate_zero_means_eight() {
+        // asr.w #8,d0
+        let (mut cpu, mut ram) = boot(&[0xE040]);
+        cpu.registers.set_data(0, 0x8180);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0xff81);
+        assert_eq!(ccr(&cpu), X | N | C);
+    }
+
+    #[test]
+    fn test_register_count_of_zero() {
+        // lsr.l d1,d0
+        let (mut cpu, mut ram) = boot(&[0xE2A8]);
+        cpu.registers.set_data(0, 5);
+        cpu.registers.set_data(1, 64);
+        cpu.registers.set_ccr((X | C) as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 5);
+        assert_eq!(ccr(&cpu), X);
+    }
+
+    #[test]
+    fn test_lsl_past_width() {
+        // lsl.l d1,d0
+        let (mut cpu, mut ram) = boot(&[0xE3A8, 0xE3A8]);
+        cpu.registers.set_data(0, 1);
+        cpu.registers.set_data(1, 32);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0);
+        assert_eq!(ccr(&cpu), X | Z | C);
+
+        cpu.registers.set_data(0, 1);
+        cpu.registers.set_data(1, 33);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0);
+        assert_eq!(ccr(&cpu), Z);
+    }
+
+    #[test]
+    fn test_roxl_through_extend() {
+        // roxl.b #1,d0
+        let (mut cpu, mut ram) = boot(&[0xE310]);
+        cpu.registers.set_data(0, 0x80);
+        cpu.registers.set_ccr(X as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x01);
+        assert_eq!(ccr(&cpu), X | C);
+    }
+
+    #[test]
+    fn test_roxr_count_of_zero_copies_extend() {
+        // roxr.w d1,d0
+        let (mut cpu, mut ram) = boot(&[0xE270]);
+        cpu.registers.set_data(0, 0x1234);
+        cpu.registers.set_data(1, 0);
+        cpu.registers.set_ccr(X as u8);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.data(0), 0x1234);
+        assert_eq!(ccr(&cpu), X | C);
+    }
+
+    #[test]
+    fn test_rotate_leaves_extend() {
+        // rol.b #1,d0
+        // ror.l #4,d0
+        let (mut cpu, mut ram) = boot(&[0xE318, 0xE898]);
+        cpu.registers.set_data(0, 0x81);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0x03);
+        assert_eq!(ccr(&cpu), C);
+
+        cpu.registers.set_data(0, 0x0f);
+        cpu.registers.set_ccr(X as u8);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(0), 0xf000_0000);
+        assert_eq!(ccr(&cpu), X | N | C);
+    }
+
+    #[test]
+    fn test_memory_shifts() {
+        // asl.w (a0)
+        // lsr.w (a0)+
+        // roxr.w -(a0)
+        let (mut cpu, mut ram) = boot(&[0xE1D0, 0xE2D8, 0xE4E0]);
+        ram.write_word(0x3000, 0x4000);
+        cpu.registers.set_address(0, 0x3000);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ram.read_word(0x3000), Some(0x8000));
+        assert_eq!(ccr(&cpu), N | V);
+
+        ram.write_word(0x3000, 0x0001);
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ram.read_word(0x3000), Some(0x0000));
+        assert_eq!(cpu.registers.address(0), 0x3002);
+        assert_eq!(ccr(&cpu), X | Z | C);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(ram.read_word(0x3000), Some(0x8000));
+        assert_eq!(cpu.registers.address(0), 0x3000);
+        assert_eq!(ccr(&cpu), N);
+    }
+}
@@ -0,0 +1,131 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_status {
+    use common::{boot, Fixture, STACK};
+    use m68k::cpu::Exception;
+    use m68k::mapped_hardware::MappedHardware;
+
+    #[test]
+    fn test_ori_to_sr() {
+        // ori #$0700,sr
+        let (mut cpu, mut ram) = boot(&[0x007C, 0x0700]);
+        cpu.registers.set_complete_ccr(0x2000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.sr(), 0x2700);
+    }
+
+    #[test]
+    fn test_andi_to_sr_switches_stack() {
+        // andi #$dfff,sr
+        let (mut cpu, mut ram) = boot(&[0x027C, 0xdfff]);
+        cpu.registers.set_usp(0x5000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.sr(), 0x0700);
+        assert_eq!(cpu.registers.sp(), 0x5000);
+    }
+
+    #[test]
+    fn test_eori_to_ccr() {
+        // eori #$1f,ccr
+        let (mut cpu, mut ram) = boot(&[0x0A3C, 0x001f]);
+        cpu.registers.set_ccr(0b0_0101);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.sr(), 0x271a);
+    }
+
+    #[test]
+    fn test_ccr_is_not_privileged() {
+        // ori #$04,ccr
+        let (mut cpu, mut ram) = boot(&[0x003C, 0x0004]);
+        cpu.registers.set_complete_ccr(0x0000);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.sr(), 0x0004);
+    }
+
+    #[test]
+    fn test_sr_is_privileged() {
+        // ori #$0700,sr
+        let (mut cpu, mut ram) = boot(&[0x007C, 0x0700]);
+        ram.set_vector(8, 0x3000);
+        cpu.registers.set_complete_ccr(0x0000);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::PrivilegeViolation));
+        assert_eq!(cpu.registers.pc(), 0x3000);
+        assert_eq!(cpu.registers.sr(), 0x2000);
+    }
+
+    #[test]
+    fn test_move_to_and_from_sr() {
+        // move.w sr,d1
+        // move.w d0,sr
+        let (mut cpu, mut ram) = boot(&[0x40C1, 0x46C0]);
+        cpu.registers.set_usp(0x5000);
+        cpu.registers.set_data(0, 0x8015);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.data(1) & 0xffff, 0x2700);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.sr(), 0x8015);
+        assert_eq!(cpu.registers.sp(), 0x5000);
+    }
+
+    #[test]
+    fn test_move_usp() {
+        // move.l a1,usp
+        // move.l usp,a2
+        let (mut cpu, mut ram) = boot(&[0x4E61, 0x4E6A]);
+        cpu.registers.set_address(1, 0x1234_5678);
+
+        cpu.execute_next_instruction(&mut ram);
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.address(2), 0x1234_5678);
+        assert_eq!(cpu.registers.sp(), STACK);
+
+        // the user stack pointer becomes A7 on the way to user mode
+        cpu.registers.set_complete_ccr(0x0000);
+        assert_eq!(cpu.registers.sp(), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_unimplemented_sr_bits_read_as_zero() {
+        // move.w #$3000,sr
+        // ori #$18e0,sr
+        let (mut cpu, mut ram) = boot(&[0x46FC, 0x3000, 0x007C, 0x18E0]);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.sr(), 0x2000);
+        assert_eq!(cpu.registers.sp(), STACK);
+
+        cpu.execute_next_instruction(&mut ram);
+        assert_eq!(cpu.registers.sr(), 0x2000);
+        assert_eq!(cpu.registers.sp(), STACK);
+    }
+
+    #[test]
+    fn test_rte_masks_sr() {
+        // rte
+        let (mut cpu, mut ram) = boot(&[0x4E73]);
+        ram.write_word(STACK, 0x7fff);
+        ram.write_long(STACK + 2, 0x3000);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.sr(), 0x271f);
+        assert_eq!(cpu.registers.pc(), 0x3000);
+        assert_eq!(cpu.registers.sp(), STACK + 6);
+    }
+}
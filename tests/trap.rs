@@ -0,0 +1,104 @@
+extern crate m68k;
+
+mod common;
+
+#[cfg(test)]
+mod test_trap {
+    use common::{boot, Fixture, PROGRAM, STACK};
+    use m68k::cpu::Exception;
+    use m68k::mapped_hardware::MappedHardware;
+
+    const N: u16 = 0b0_1000;
+    const V: u16 = 0b0_0010;
+    const HANDLER: u32 = 0x3000;
+
+    #[test]
+    fn test_trap_from_user_mode() {
+        // trap #3
+        let (mut cpu, mut ram) = boot(&[0x4E43]);
+        ram.set_vector(35, HANDLER);
+        cpu.registers.set_complete_ccr(0x0000);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Trap(3)));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sr(), 0x2000);
+        assert_eq!(cpu.registers.sp(), STACK - 6);
+        assert_eq!(ram.read_word(STACK - 6), Some(0x0000));
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_trapv() {
+        // trapv
+        let (mut cpu, mut ram) = boot(&[0x4E76, 0x4E76]);
+        ram.set_vector(7, HANDLER);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 2);
+
+        cpu.registers.set_ccr(V as u8);
+        let exception = cpu.execute_next_instruction(&mut ram);
+        assert_eq!(exception, Some(Exception::TrapV));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 4));
+    }
+
+    #[test]
+    fn test_chk_in_bounds() {
+        // chk.w d1,d0
+        let (mut cpu, mut ram) = boot(&[0x4181]);
+        cpu.registers.set_data(0, 10);
+        cpu.registers.set_data(1, 10);
+
+        assert_eq!(cpu.execute_next_instruction(&mut ram), None);
+        assert_eq!(cpu.registers.pc(), PROGRAM + 2);
+    }
+
+    #[test]
+    fn test_chk_above_bound() {
+        // chk.w d1,d0
+        let (mut cpu, mut ram) = boot(&[0x4181]);
+        ram.set_vector(6, HANDLER);
+        cpu.registers.set_data(0, 11);
+        cpu.registers.set_data(1, 10);
+        cpu.registers.set_ccr(N as u8);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Chk));
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sr() & N, 0);
+        assert_eq!(ram.read_long(STACK - 4), Some(PROGRAM + 2));
+    }
+
+    #[test]
+    fn test_chk_negative() {
+        // chk.w d1,d0
+        let (mut cpu, mut ram) = boot(&[0x4181]);
+        ram.set_vector(6, HANDLER);
+        cpu.registers.set_data(0, 0xffff);
+        cpu.registers.set_data(1, 10);
+
+        let exception = cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(exception, Some(Exception::Chk));
+        assert_eq!(cpu.registers.sr() & N, N);
+    }
+
+    #[test]
+    fn test_rtr() {
+        // rtr
+        let (mut cpu, mut ram) = boot(&[0x4E77]);
+        ram.write_word(STACK - 6, 0xff1f);
+        ram.write_long(STACK - 4, HANDLER);
+        cpu.registers.set_sp(STACK - 6);
+
+        cpu.execute_next_instruction(&mut ram);
+
+        assert_eq!(cpu.registers.pc(), HANDLER);
+        assert_eq!(cpu.registers.sr(), 0x271f);
+        assert_eq!(cpu.registers.sp(), STACK);
+    }
+}